@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS users (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	username TEXT NOT NULL UNIQUE,
+	created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS runs (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	user_id INTEGER NOT NULL REFERENCES users(id),
+	score INT NOT NULL,
+	created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE IF NOT EXISTS credits (
+	run_id INTEGER NOT NULL REFERENCES runs(id),
+	question_id TEXT NOT NULL,
+	credit REAL NOT NULL,
+	PRIMARY KEY (run_id, question_id)
+);
+
+INSERT OR IGNORE INTO users (username, created_at)
+	SELECT username, created_at FROM scores;
+
+INSERT INTO runs (user_id, score, created_at)
+	SELECT users.id, scores.score, scores.created_at
+	FROM scores JOIN users USING (username)
+	ORDER BY scores.id;
+
+DROP TABLE scores
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS progress (
+	username TEXT NOT NULL,
+	question_id TEXT NOT NULL,
+	is_solved BOOLEAN NOT NULL DEFAULT 0,
+	attempts INT NOT NULL DEFAULT 0,
+	best_query TEXT,
+	best_credit REAL NOT NULL DEFAULT 0,
+	time_spent INT NOT NULL DEFAULT 0,
+	updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+	PRIMARY KEY (username, question_id)
+)
@@ -0,0 +1,33 @@
+-- Question ids are only unique within a pack, so everything keyed by them
+-- also records the pack id. Runs recorded before packs existed
+-- keep a NULL pack, since their mode was never stored.
+ALTER TABLE runs ADD COLUMN pack TEXT;
+
+ALTER TABLE credits ADD COLUMN pack TEXT;
+
+-- Only the practice pack saved progress until now.
+CREATE TABLE progress_by_pack (
+	user_id INTEGER NOT NULL REFERENCES users(id),
+	pack TEXT NOT NULL,
+	question_id TEXT NOT NULL,
+	is_solved BOOLEAN NOT NULL DEFAULT 0,
+	attempts INT NOT NULL DEFAULT 0,
+	best_query TEXT,
+	best_credit REAL NOT NULL DEFAULT 0,
+	time_spent INT NOT NULL DEFAULT 0,
+	updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+	PRIMARY KEY (user_id, pack, question_id)
+);
+
+INSERT OR IGNORE INTO users (username)
+	SELECT DISTINCT username FROM progress;
+
+INSERT INTO progress_by_pack
+	SELECT users.id, 'practice', progress.question_id, progress.is_solved,
+		progress.attempts, progress.best_query, progress.best_credit,
+		progress.time_spent, progress.updated_at
+	FROM progress JOIN users USING (username);
+
+DROP TABLE progress;
+
+ALTER TABLE progress_by_pack RENAME TO progress
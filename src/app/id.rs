@@ -2,6 +2,7 @@
 pub enum Id {
     GlobalListener,
     Help,
+    PackList,
     ScoreTable,
     UsernameInput,
     SchemaView,
@@ -10,4 +11,6 @@ pub enum Id {
     Editor,
     Result,
     Question,
+    QuestionList,
+    Error,
 }
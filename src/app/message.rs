@@ -6,9 +6,16 @@ pub enum Message {
     ToggleHelp,
     Start(String),
     ToggleSchema,
+    ToggleQuestionList,
     Run,
     Submit,
+    Cancel,
+    CloseError,
+    QueryFinished(u64),
     NextQuestion,
+    Skip,
+    GoTo(usize),
+    SelectPack(usize),
     End,
     ChangeScreen(Screen),
     Active(isize),
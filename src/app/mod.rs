@@ -1,37 +1,66 @@
 mod id;
 mod message;
 mod screen;
+mod user_event;
+mod worker;
 
-use std::time::Duration;
+use std::{
+    collections::BTreeSet,
+    time::{Duration, Instant},
+};
 
 use ratatui::layout::{Constraint, Layout, Rect};
 use tuirealm::{
-    Application, AttrValue, Attribute, Component, EventListenerCfg, NoUserEvent, Sub, SubClause,
-    SubEventClause, Update,
+    Application, AttrValue, Attribute, Component, EventListenerCfg, Sub, SubClause, SubEventClause,
+    Update,
     props::{PropPayload, PropValue},
     terminal::{CrosstermTerminalAdapter, TerminalAdapter, TerminalBridge},
 };
 
 use crate::{
     component::{
-        Editor, GlobalListener, Help, QueryError, Question, ResultTable, SchemaView, Score,
-        ScoreTable, Timer, UsernameInput,
+        Editor, ErrorPopup, GlobalListener, Help, PackList, QueryError, Question, QuestionEntry,
+        QuestionList, ResultDiff, ResultTable, SchemaView, Score, ScoreTable, Spinner, Timer,
+        UsernameInput,
+    },
+    config::{CONFIG, Mode},
+    repository::{self, Database, pack::Pack, progress::Progress, question::Kind},
+    util::{
+        self,
+        query::Error,
+        scoring::{self, Attempt},
     },
-    config::CONFIG,
-    repository, util,
 };
 
 pub use id::*;
 pub use message::*;
 pub use screen::*;
+pub use user_event::*;
+pub use worker::*;
 
 pub struct App<T: TerminalAdapter> {
-    pub inner: Application<Id, Message, NoUserEvent>,
+    pub inner: Application<Id, Message, UserEvent>,
+    pub worker: Worker,
+    pub database: Database,
+    /// Shown once the current message has been handled.
+    pub pending_error: Option<String>,
 
     pub username: Option<String>,
 
+    pub packs: Vec<Pack>,
+    /// Pack played in the current game, or selected on the home screen.
+    pub pack_index: usize,
     pub questions: Vec<repository::question::Question>,
     pub question_index: usize,
+    pub attempts: Vec<Attempt>,
+    pub skipped: BTreeSet<usize>,
+    /// Questions already solved in an earlier practice session. They stay
+    /// solved but count toward neither this run's score nor its credits.
+    pub carried_over: BTreeSet<usize>,
+    pub drafts: Vec<String>,
+    pub progress: Vec<Progress>,
+    /// When the player arrived at the current question.
+    pub entered_at: Instant,
 
     pub screen: Screen,
     pub quit: bool,
@@ -39,24 +68,38 @@ pub struct App<T: TerminalAdapter> {
     pub terminal: TerminalBridge<T>,
 }
 
-impl Default for App<CrosstermTerminalAdapter> {
-    fn default() -> Self {
+impl App<CrosstermTerminalAdapter> {
+    /// Takes what can fail to load up front, so startup errors are reported
+    /// before the terminal is taken over.
+    pub fn new(packs: Vec<Pack>, database: Database) -> Self {
+        let (worker, worker_port) = Worker::new();
+
         let inner = Application::init(
             EventListenerCfg::default()
                 .crossterm_input_listener(Duration::from_millis(20), 3)
+                .add_port(Box::new(worker_port), Duration::from_millis(20), 1)
                 .poll_timeout(Duration::from_millis(10))
                 .tick_interval(Duration::from_secs(CONFIG.tick_rate)),
         );
 
-        let questions = repository::question::get_all().unwrap();
-
         let mut app = Self {
             inner,
+            worker,
+            database,
+            pending_error: None,
 
             username: None,
 
-            questions,
+            packs,
+            pack_index: 0,
+            questions: Vec::new(),
             question_index: 0,
+            attempts: Vec::new(),
+            skipped: BTreeSet::new(),
+            carried_over: BTreeSet::new(),
+            drafts: Vec::new(),
+            progress: Vec::new(),
+            entered_at: Instant::now(),
 
             screen: Screen::Home,
             quit: false,
@@ -66,6 +109,11 @@ impl Default for App<CrosstermTerminalAdapter> {
 
         app.change_screen(Screen::Home);
 
+        // Nothing has been handled yet for `update` to show it after.
+        if app.pending_error.is_some() {
+            app.show_error();
+        }
+
         app
     }
 }
@@ -78,26 +126,41 @@ where
         let message = message?;
         self.redraw = true;
 
-        match message {
+        let next = match message {
             Message::Quit => self.quit(),
             Message::ToggleHelp => self.toggle(&Id::Help),
             Message::Start(username) => self.start(username),
             Message::ToggleSchema => self.toggle(&Id::SchemaView),
+            Message::ToggleQuestionList => self.toggle_question_list(),
             Message::Run => self.run(),
             Message::Submit => self.submit(),
+            Message::Cancel => self.cancel(),
+            Message::QueryFinished(id) => self.query_finished(id),
             Message::NextQuestion => self.next_question(),
+            Message::Skip => self.skip(),
+            Message::GoTo(index) => self.go_to_question(index),
+            Message::SelectPack(index) => self.select_pack(index),
             Message::End => self.end(),
             Message::ChangeScreen(screen) => self.change_screen(screen),
             Message::Active(offset) => self.active(offset),
+            Message::CloseError => self.close_error(),
             Message::None => None,
+        };
+
+        // Wait until the follow-up messages are done, so a screen change does
+        // not unmount the popup right away.
+        if next.is_none() && self.pending_error.is_some() {
+            self.show_error();
         }
+
+        next
     }
 }
 
 impl<T: TerminalAdapter> App<T> {
     fn get_components(focus: Option<&Id>, screen: Screen, area: Rect) -> Vec<(Id, Rect)> {
         match focus {
-            Some(Id::Help) => {
+            Some(id @ (Id::Help | Id::SchemaView | Id::QuestionList | Id::Error)) => {
                 let chunks = Layout::horizontal([
                     Constraint::Min(0),
                     Constraint::Max(80),
@@ -108,20 +171,7 @@ impl<T: TerminalAdapter> App<T> {
                     Layout::vertical([Constraint::Min(0), Constraint::Max(80), Constraint::Min(0)])
                         .split(chunks[1]);
 
-                return vec![(Id::Help, chunks[1])];
-            }
-            Some(Id::SchemaView) => {
-                let chunks = Layout::horizontal([
-                    Constraint::Min(0),
-                    Constraint::Max(80),
-                    Constraint::Min(0),
-                ])
-                .split(area);
-                let chunks =
-                    Layout::vertical([Constraint::Min(0), Constraint::Max(80), Constraint::Min(0)])
-                        .split(chunks[1]);
-
-                return vec![(Id::SchemaView, chunks[1])];
+                return vec![(id.clone(), chunks[1])];
             }
             _ => {}
         }
@@ -136,10 +186,18 @@ impl<T: TerminalAdapter> App<T> {
                 .margin(2)
                 .split(area);
 
-                let chunks = Layout::vertical([Constraint::Min(0), Constraint::Length(3)])
-                    .split(margined_chunks[1]);
+                let chunks = Layout::vertical([
+                    Constraint::Length(8),
+                    Constraint::Min(0),
+                    Constraint::Length(3),
+                ])
+                .split(margined_chunks[1]);
 
-                vec![(Id::ScoreTable, chunks[0]), (Id::UsernameInput, chunks[1])]
+                vec![
+                    (Id::PackList, chunks[0]),
+                    (Id::ScoreTable, chunks[1]),
+                    (Id::UsernameInput, chunks[2]),
+                ]
             }
             Screen::Game => {
                 let chunks =
@@ -191,11 +249,113 @@ impl<T: TerminalAdapter> App<T> {
 
     fn start(&mut self, username: String) -> Option<Message> {
         self.username = Some(username);
+        self.questions = self.packs[self.pack_index].questions.clone();
         self.question_index = 0;
+        self.attempts = vec![Attempt::default(); self.questions.len()];
+        self.skipped.clear();
+        self.carried_over.clear();
+        self.drafts = vec![String::new(); self.questions.len()];
+        self.progress = self
+            .questions
+            .iter()
+            .map(|question| Progress::new(question.id.clone()))
+            .collect();
+
+        if self.mode() == Mode::Practice {
+            self.resume();
+        }
 
         Some(Message::ChangeScreen(Screen::Game))
     }
 
+    /// Restores the player's saved progress and moves to the first question
+    /// they have not solved yet.
+    fn resume(&mut self) {
+        let username = self.username.as_ref().unwrap();
+
+        let pack = &self.packs[self.pack_index].id;
+
+        let saved_progress = match self.database.get_progress(username, pack) {
+            Ok(saved_progress) => saved_progress,
+            Err(error) => {
+                self.pending_error = Some(error.to_string());
+                Vec::new()
+            }
+        };
+
+        for saved in saved_progress {
+            let Some(index) = self
+                .questions
+                .iter()
+                .position(|question| question.id == saved.question_id)
+            else {
+                continue;
+            };
+
+            if saved.is_solved {
+                self.attempts[index].solve(0);
+                self.carried_over.insert(index);
+            }
+            if let Some(query) = &saved.best_query {
+                self.drafts[index] = query.clone();
+            }
+            self.progress[index] = saved;
+        }
+
+        self.question_index = self
+            .attempts
+            .iter()
+            .position(|attempt| !attempt.is_solved())
+            .unwrap_or(0);
+    }
+
+    /// Adds the time spent on the current question to its progress and saves
+    /// it. Only practice progress is kept.
+    fn save_progress(&mut self) {
+        let pack = &self.packs[self.pack_index];
+        let progress = &mut self.progress[self.question_index];
+        progress.time_spent += self.entered_at.elapsed();
+        self.entered_at = Instant::now();
+
+        if pack.mode != Mode::Practice {
+            return;
+        }
+
+        if let Some(username) = &self.username
+            && let Err(error) = self.database.save_progress(username, &pack.id, progress)
+        {
+            self.pending_error = Some(error.to_string());
+        }
+    }
+
+    fn record_submission(&mut self, query: String, credit: f64) {
+        let progress = &mut self.progress[self.question_index];
+        progress.attempts += 1;
+
+        if !progress.is_solved && (credit > progress.best_credit || progress.best_query.is_none()) {
+            progress.is_solved = credit >= 1.0;
+            progress.best_credit = credit;
+            progress.best_query = Some(query);
+        }
+
+        self.save_progress();
+    }
+
+    /// The question list lets practice players jump around freely, which
+    /// would defeat the point of the timer in a challenge.
+    fn toggle_question_list(&mut self) -> Option<Message> {
+        if self.mode() != Mode::Practice || self.screen != Screen::Game {
+            return None;
+        }
+
+        // Refresh the solved status before showing the list.
+        if self.inner.focus() != Some(&Id::QuestionList) {
+            self.remount(Id::QuestionList);
+        }
+
+        self.toggle(&Id::QuestionList)
+    }
+
     fn get_query(&self) -> String {
         self.inner
             .state(&Id::Editor)
@@ -204,49 +364,210 @@ impl<T: TerminalAdapter> App<T> {
             .unwrap_string()
     }
 
-    fn run(&mut self) -> Option<Message> {
-        let schema = self.current_question().schema.raw.as_str();
-        let query = self.get_query();
-
-        let component: Box<dyn Component<_, _>> = match util::query::run(&query, schema) {
-            Ok(data) => Box::new(ResultTable::new(Some(data))),
-            Err(error) => Box::new(QueryError::new(error.to_string())),
-        };
-
+    fn show_result(&mut self, component: Box<dyn Component<Message, UserEvent>>) {
         self.inner
             .remount(Id::Result, component, Vec::new())
             .unwrap();
+    }
+
+    fn show_running(&mut self) {
+        self.inner
+            .remount(
+                Id::Result,
+                Box::new(Spinner::default()),
+                vec![Sub::new(SubEventClause::Tick, SubClause::Always)],
+            )
+            .unwrap();
+    }
+
+    fn run(&mut self) -> Option<Message> {
+        if self.screen != Screen::Game {
+            return None;
+        }
+        if self.worker.is_running() {
+            return self.cancel();
+        }
+
+        let current_question = self.current_question();
+
+        let database = current_question.schema.snapshot.clone();
+        let access = current_question.access;
+        let query = self.get_query();
+
+        self.worker.spawn(move |cancel| {
+            Outcome::Run(util::query::run_all(&query, &database, access, cancel))
+        });
+        self.show_running();
 
         None
     }
 
     fn submit(&mut self) -> Option<Message> {
-        let current_question = self.current_question();
+        if self.screen != Screen::Game || self.worker.is_running() {
+            return None;
+        }
 
-        let schema = current_question.schema.raw.as_str();
+        let question = self.current_question().clone();
         let user_query = self.get_query();
-        let answer_query = current_question.answer.as_str();
+        let explain = self.mode() == Mode::Practice;
+
+        self.worker.spawn(move |cancel| {
+            let result = question.check(&user_query, cancel).and_then(|is_correct| {
+                if is_correct {
+                    return Ok(Verdict::Correct);
+                }
+
+                // Only result questions can be compared row by row.
+                if question.kind != Kind::Result {
+                    return Ok(Verdict::Incorrect(0.0, None));
+                }
+
+                let diffs = question.diffs(&user_query, cancel)?;
+                let credit = scoring::lowest_credit(&diffs);
+                let diff = diffs
+                    .into_iter()
+                    .next()
+                    .filter(|diff| explain && !diff.is_empty());
 
-        let error = match util::query::is_equal(&user_query, answer_query, schema) {
-            Ok(true) => return Some(Message::NextQuestion),
-            Ok(false) => "Incorrect answer".to_string(),
-            Err(error) => error.to_string(),
+                Ok(Verdict::Incorrect(credit, diff))
+            });
+
+            Outcome::Submit(user_query, result)
+        });
+        self.show_running();
+
+        None
+    }
+
+    fn show_error(&mut self) {
+        self.remount(Id::Error);
+        self.inner.active(&Id::Error).unwrap();
+    }
+
+    fn close_error(&mut self) -> Option<Message> {
+        if self.inner.focus() == Some(&Id::Error) {
+            self.inner.blur().unwrap();
+        }
+        self.inner.umount(&Id::Error).unwrap();
+
+        None
+    }
+
+    fn cancel(&mut self) -> Option<Message> {
+        if self.worker.cancel() {
+            self.show_result(Box::new(QueryError::new(Error::Cancelled.to_string())));
+        }
+
+        None
+    }
+
+    fn query_finished(&mut self, id: u64) -> Option<Message> {
+        let component: Box<dyn Component<_, _>> = match self.worker.take(id)? {
+            Outcome::Run(Ok(results)) => Box::new(ResultTable::new(results)),
+            Outcome::Submit(query, Ok(Verdict::Correct)) => {
+                self.record_submission(query, 1.0);
+
+                let seconds_left = self.seconds_left();
+                self.current_attempt().solve(seconds_left);
+                self.remount(Id::Score);
+
+                return Some(Message::NextQuestion);
+            }
+            Outcome::Submit(query, Ok(Verdict::Incorrect(credit, diff))) => {
+                self.record_submission(query, credit);
+                self.current_attempt().fail(credit);
+                self.remount(Id::Score);
+
+                match diff {
+                    Some(diff) => Box::new(ResultDiff::new(diff)),
+                    None => Box::new(QueryError::new("Incorrect answer".to_string())),
+                }
+            }
+            Outcome::Run(Err(error)) | Outcome::Submit(_, Err(error)) => {
+                Box::new(QueryError::new(error.to_string()))
+            }
         };
 
-        self.inner
-            .remount(Id::Result, Box::new(QueryError::new(error)), Vec::new())
-            .unwrap();
+        self.show_result(component);
 
         None
     }
 
+    fn seconds_left(&self) -> u64 {
+        self.inner
+            .state(&Id::Timer)
+            .unwrap()
+            .unwrap_one()
+            .unwrap_u64()
+    }
+
+    /// Indices of the questions played in this run, leaving out those carried
+    /// over from an earlier session.
+    fn played(&self) -> Vec<usize> {
+        (0..self.questions.len())
+            .filter(|index| !self.carried_over.contains(index))
+            .collect()
+    }
+
+    fn total_score(&self) -> u64 {
+        self.played()
+            .into_iter()
+            .map(|index| {
+                self.attempts[index].score(self.questions[index].points, CONFIG.game_duration)
+            })
+            .sum()
+    }
+
+    /// The first unsolved question after the current one, wrapping around to
+    /// the skipped ones once the end of the list is reached.
+    fn next_unsolved(&self) -> Option<usize> {
+        let count = self.questions.len();
+
+        (1..count)
+            .map(|offset| (self.question_index + offset) % count)
+            .find(|&index| !self.attempts[index].is_solved())
+    }
+
     fn next_question(&mut self) -> Option<Message> {
-        self.question_index += 1;
+        match self.next_unsolved() {
+            Some(index) => self.go_to(index),
+            None => Some(Message::End),
+        }
+    }
 
-        if self.question_index == self.questions.len() {
-            return Some(Message::End);
+    fn skip(&mut self) -> Option<Message> {
+        if self.screen != Screen::Game || self.worker.is_running() {
+            return None;
         }
 
+        // Nothing to skip to when this is the last unsolved question.
+        let index = self.next_unsolved()?;
+
+        self.go_to(index)
+    }
+
+    fn go_to_question(&mut self, index: usize) -> Option<Message> {
+        self.inner.blur().unwrap();
+
+        if index == self.question_index || self.worker.is_running() {
+            return None;
+        }
+
+        self.go_to(index)
+    }
+
+    /// Switches to the question at `index`, keeping the draft of the current
+    /// one and counting it as skipped if it is still unsolved.
+    fn go_to(&mut self, index: usize) -> Option<Message> {
+        self.save_progress();
+        self.drafts[self.question_index] = self.get_query();
+        if !self.attempts[self.question_index].is_solved() {
+            self.skipped.insert(self.question_index);
+        }
+
+        self.question_index = index;
+        self.skipped.remove(&index);
+
         self.remount(Id::SchemaView);
         self.remount(Id::Editor);
         self.remount(Id::Question);
@@ -259,11 +580,27 @@ impl<T: TerminalAdapter> App<T> {
     }
 
     fn end(&mut self) -> Option<Message> {
-        let Some(username) = self.username.as_ref() else {
+        let Some(username) = self.username.clone() else {
             return Some(Message::Quit);
         };
 
-        repository::score::insert(username, self.question_index as u64).unwrap();
+        self.save_progress();
+
+        let credits = self
+            .played()
+            .into_iter()
+            .map(|index| {
+                (
+                    self.questions[index].id.as_str(),
+                    self.attempts[index].credit,
+                )
+            })
+            .collect::<Vec<_>>();
+        let score = self.total_score();
+        let pack = &self.packs[self.pack_index].id;
+        if let Err(error) = self.database.insert_run(&username, pack, score, &credits) {
+            self.pending_error = Some(error.to_string());
+        }
 
         self.question_index = 0;
 
@@ -279,8 +616,20 @@ impl<T: TerminalAdapter> App<T> {
 
             Id::Help => (Box::new(Help::default()), Vec::new()),
 
+            Id::PackList => (
+                Box::new(PackList::new(&self.packs, self.pack_index)),
+                Vec::new(),
+            ),
+
             Id::ScoreTable => {
-                let scores = repository::score::get_all().unwrap();
+                let pack = &self.packs[self.pack_index].id;
+                let scores = self
+                    .database
+                    .get_scores(Some(pack))
+                    .unwrap_or_else(|error| {
+                        self.pending_error = Some(error.to_string());
+                        Vec::new()
+                    });
 
                 (Box::new(ScoreTable::new(scores)), Vec::new())
             }
@@ -302,21 +651,64 @@ impl<T: TerminalAdapter> App<T> {
                 vec![Sub::new(SubEventClause::Tick, SubClause::Always)],
             ),
 
-            Id::Score => (Box::new(Score::new(self.question_index as u64)), Vec::new()),
+            Id::Score => (Box::new(Score::new(self.total_score())), Vec::new()),
 
             Id::Question => (
-                Box::new(Question::new(self.current_question().question.clone())),
+                Box::new(Question::new(
+                    self.current_question().question.clone(),
+                    self.progress(),
+                )),
+                Vec::new(),
+            ),
+
+            Id::Result => (Box::new(ResultTable::new(Vec::new())), Vec::new()),
+
+            Id::QuestionList => {
+                let entries = self
+                    .questions
+                    .iter()
+                    .zip(&self.attempts)
+                    .map(|(question, attempt)| QuestionEntry {
+                        question: question.question.clone(),
+                        is_solved: attempt.is_solved(),
+                    })
+                    .collect();
+
+                (
+                    Box::new(QuestionList::new(entries, self.question_index)),
+                    Vec::new(),
+                )
+            }
+
+            Id::Error => (
+                Box::new(ErrorPopup::new(
+                    self.pending_error.take().unwrap_or_default(),
+                )),
                 Vec::new(),
             ),
 
-            Id::Result => (Box::new(ResultTable::new(None)), Vec::new()),
+            Id::Editor => {
+                let draft = &self.drafts[self.question_index];
 
-            Id::Editor => (Box::new(Editor::default()), Vec::new()),
+                (
+                    Box::new(Editor::new(draft.lines().map(String::from).collect())),
+                    Vec::new(),
+                )
+            }
         };
 
         self.inner.remount(id, component, subs).unwrap();
     }
 
+    /// Highlights the pack the next game is played with and shows its
+    /// scores.
+    fn select_pack(&mut self, index: usize) -> Option<Message> {
+        self.pack_index = index;
+        self.remount(Id::ScoreTable);
+
+        None
+    }
+
     fn select_previous_user(&mut self) {
         let Some(username) = &self.username else {
             return;
@@ -329,10 +721,11 @@ impl<T: TerminalAdapter> App<T> {
             .unwrap()
             .unwrap_table();
 
-        let row_index = data
-            .iter()
-            .position(|row| &row[0].content == username)
-            .unwrap();
+        // The run may have failed to save.
+        let Some(row_index) = data.iter().position(|row| &row[0].content == username) else {
+            self.username = None;
+            return;
+        };
 
         self.inner
             .attr(
@@ -347,6 +740,7 @@ impl<T: TerminalAdapter> App<T> {
 
     fn change_screen(&mut self, screen: Screen) -> Option<Message> {
         self.screen = screen;
+        self.worker.cancel();
 
         self.inner.umount_all();
 
@@ -355,6 +749,7 @@ impl<T: TerminalAdapter> App<T> {
 
         match screen {
             Screen::Home => {
+                self.remount(Id::PackList);
                 self.remount(Id::ScoreTable);
                 self.remount(Id::UsernameInput);
                 self.select_previous_user();
@@ -362,12 +757,17 @@ impl<T: TerminalAdapter> App<T> {
                 self.inner.active(&Id::UsernameInput).unwrap();
             }
             Screen::Game => {
+                self.entered_at = Instant::now();
+
                 self.remount(Id::SchemaView);
                 self.remount(Id::Timer);
                 self.remount(Id::Score);
                 self.remount(Id::Question);
                 self.remount(Id::Result);
                 self.remount(Id::Editor);
+                if self.mode() == Mode::Practice {
+                    self.remount(Id::QuestionList);
+                }
 
                 self.inner.active(&Id::Editor).unwrap();
             }
@@ -377,7 +777,7 @@ impl<T: TerminalAdapter> App<T> {
     }
 
     fn active(&mut self, offset: isize) -> Option<Message> {
-        if [Id::Help, Id::SchemaView]
+        if [Id::Help, Id::SchemaView, Id::QuestionList, Id::Error]
             .map(Some)
             .contains(&self.inner.focus().cloned())
         {
@@ -385,7 +785,7 @@ impl<T: TerminalAdapter> App<T> {
         }
 
         let active_list = match self.screen {
-            Screen::Home => [Id::ScoreTable, Id::UsernameInput].as_slice(),
+            Screen::Home => [Id::PackList, Id::ScoreTable, Id::UsernameInput].as_slice(),
             Screen::Game => [Id::Editor, Id::Result, Id::Question].as_slice(),
         };
         let count = active_list.len() as isize;
@@ -411,7 +811,25 @@ impl<T: TerminalAdapter> App<T> {
         None
     }
 
+    /// Mode of the pack being played.
+    fn mode(&self) -> Mode {
+        self.packs[self.pack_index].mode
+    }
+
     fn current_question(&self) -> &repository::question::Question {
         &self.questions[self.question_index]
     }
+
+    fn progress(&self) -> String {
+        let progress = format!("{}/{}", self.question_index + 1, self.questions.len());
+
+        match self.skipped.len() {
+            0 => progress,
+            skipped => format!("{progress} ({skipped} skipped)"),
+        }
+    }
+
+    fn current_attempt(&mut self) -> &mut Attempt {
+        &mut self.attempts[self.question_index]
+    }
 }
@@ -0,0 +1,4 @@
+#[derive(Debug, PartialEq, Eq, PartialOrd, Clone)]
+pub enum UserEvent {
+    QueryFinished(u64),
+}
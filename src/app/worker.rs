@@ -0,0 +1,122 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+};
+
+use tuirealm::{
+    Event,
+    listener::{ListenerResult, Poll},
+};
+
+use crate::{
+    app::UserEvent,
+    util::{
+        diff::Diff,
+        query::{self, CancelToken, StatementResult},
+    },
+};
+
+pub enum Verdict {
+    Correct,
+    /// The partial credit earned, and the diff to show the player if any.
+    Incorrect(f64, Option<Diff>),
+}
+
+pub enum Outcome {
+    Run(Result<Vec<StatementResult>, query::Error>),
+    /// The submitted query and how it was graded.
+    Submit(String, Result<Verdict, query::Error>),
+}
+
+/// Runs player queries on background threads so the UI keeps responding.
+pub struct Worker {
+    next_id: u64,
+    running: Option<(u64, CancelToken)>,
+    outcomes: Arc<Mutex<HashMap<u64, Outcome>>>,
+    sender: Sender<u64>,
+}
+
+/// Event port notifying the application when a query has finished.
+pub struct WorkerPort {
+    receiver: Receiver<u64>,
+}
+
+impl Poll<UserEvent> for WorkerPort {
+    fn poll(&mut self) -> ListenerResult<Option<Event<UserEvent>>> {
+        Ok(self
+            .receiver
+            .try_recv()
+            .ok()
+            .map(|id| Event::User(UserEvent::QueryFinished(id))))
+    }
+}
+
+impl Worker {
+    pub fn new() -> (Self, WorkerPort) {
+        let (sender, receiver) = mpsc::channel();
+
+        let worker = Self {
+            next_id: 0,
+            running: None,
+            outcomes: Arc::default(),
+            sender,
+        };
+
+        (worker, WorkerPort { receiver })
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    pub fn spawn<F>(&mut self, job: F)
+    where
+        F: FnOnce(&CancelToken) -> Outcome + Send + 'static,
+    {
+        self.cancel();
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let cancel = CancelToken::default();
+        self.running = Some((id, cancel.clone()));
+
+        let outcomes = self.outcomes.clone();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let outcome = job(&cancel);
+
+            outcomes.lock().unwrap().insert(id, outcome);
+            let _ = sender.send(id);
+        });
+    }
+
+    /// Aborts the in-flight query, returning whether there was one.
+    pub fn cancel(&mut self) -> bool {
+        match self.running.take() {
+            Some((_, cancel)) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes the outcome of job `id`, discarding it if the job was cancelled
+    /// or superseded in the meantime.
+    pub fn take(&mut self, id: u64) -> Option<Outcome> {
+        let outcome = self.outcomes.lock().unwrap().remove(&id)?;
+
+        match self.running {
+            Some((running_id, _)) if running_id == id => {
+                self.running = None;
+                Some(outcome)
+            }
+            _ => None,
+        }
+    }
+}
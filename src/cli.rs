@@ -0,0 +1,76 @@
+use clap::{Args, Parser, Subcommand};
+
+use crate::config::{Mode, Overrides};
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Config file read on top of the system and user ones
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<String>,
+
+    /// Score database to use instead of the configured one
+    #[arg(long, global = true, value_name = "PATH")]
+    pub database: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Play a game (the default)
+    Play(PlayArgs),
+    /// Print the leaderboard
+    Leaderboard,
+    /// Check that question packs load and their answers are sound
+    ValidatePack(ValidatePackArgs),
+}
+
+#[derive(Debug, Default, Args)]
+pub struct PlayArgs {
+    /// Only offer packs of this mode
+    #[arg(long)]
+    pub mode: Option<Mode>,
+
+    /// Game duration in seconds
+    #[arg(long)]
+    pub duration: Option<u64>,
+
+    /// Pack file or directory of packs to offer instead of the built-in and
+    /// configured ones, may be repeated. The first one is preselected
+    #[arg(long = "pack", value_name = "PATH")]
+    pub packs: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ValidatePackArgs {
+    /// Pack files or directories of packs to check, the built-in and
+    /// configured ones by default
+    pub packs: Vec<String>,
+
+    /// Only check packs of this mode
+    #[arg(long)]
+    pub mode: Option<Mode>,
+}
+
+impl Cli {
+    pub fn overrides(&self) -> Overrides {
+        let mut overrides = Overrides {
+            config_file: self.config.clone(),
+            database_file: self.database.clone(),
+            ..Overrides::default()
+        };
+
+        match &self.command {
+            Some(Command::Play(args)) => {
+                overrides.mode = args.mode;
+                overrides.game_duration = args.duration;
+            }
+            Some(Command::ValidatePack(args)) => overrides.mode = args.mode,
+            Some(Command::Leaderboard) | None => {}
+        }
+
+        overrides
+    }
+}
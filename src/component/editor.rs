@@ -1,13 +1,13 @@
 use autumnus::{languages::Language, themes};
 use tuirealm::{
-    Component, Event, MockComponent, NoUserEvent,
+    Component, Event, MockComponent,
     command::{Cmd, Direction, Position},
     event::{Key, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind},
     props::{Alignment, BorderSides, Borders, Color, Style, TextModifiers},
 };
 
 use crate::{
-    app::Message,
+    app::{Message, UserEvent},
     component::textarea::{self, TextArea},
 };
 
@@ -18,9 +18,15 @@ pub struct Editor<'a> {
 
 impl Default for Editor<'_> {
     fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl Editor<'_> {
+    pub fn new(lines: Vec<String>) -> Self {
         Self {
             component: TextArea::new(
-                Vec::new(),
+                lines,
                 Language::SQL,
                 themes::get("vscode_dark").unwrap().clone(),
             )
@@ -45,8 +51,8 @@ impl Default for Editor<'_> {
     }
 }
 
-impl Component<Message, NoUserEvent> for Editor<'_> {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for Editor<'_> {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         let cmd = match event {
             Event::Keyboard(KeyEvent {
                 code: Key::Backspace,
@@ -148,7 +154,7 @@ impl Component<Message, NoUserEvent> for Editor<'_> {
             }) => textarea::command::REDO,
 
             Event::Keyboard(KeyEvent {
-                code: Key::Char('r') | Key::Char('s') | Key::Char('t'),
+                code: Key::Char('r' | 's' | 't' | 'n' | 'l'),
                 modifiers: KeyModifiers::CONTROL,
             }) => Cmd::None,
 
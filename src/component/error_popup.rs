@@ -0,0 +1,56 @@
+use tui_realm_stdlib::Textarea;
+use tuirealm::{
+    Component, Event, MockComponent,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderSides, Borders, Color, Style, TextSpan},
+};
+
+use crate::app::{Message, UserEvent};
+
+/// Reports a failure that is not tied to a query, until dismissed.
+#[derive(MockComponent)]
+pub struct ErrorPopup {
+    component: Textarea,
+}
+
+impl ErrorPopup {
+    pub fn new(error: String) -> Self {
+        Self {
+            component: Textarea::default()
+                .borders(
+                    Borders::default()
+                        .sides(BorderSides::all())
+                        .color(Color::Red),
+                )
+                .inactive(Style::default().fg(Color::Red))
+                .title("Error", Alignment::Center)
+                .text_rows(error.lines().map(TextSpan::new)),
+        }
+    }
+}
+
+impl Component<Message, UserEvent> for ErrorPopup {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
+        let _ = match event {
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter | Key::Esc,
+                ..
+            }) => return Some(Message::CloseError),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Scroll(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            _ => CmdResult::None,
+        };
+        Some(Message::None)
+    }
+}
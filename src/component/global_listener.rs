@@ -1,19 +1,19 @@
 use tui_realm_stdlib::Phantom;
 use tuirealm::{
-    Component, Event, NoUserEvent,
+    Component, Event,
     event::{Key, KeyEvent, KeyModifiers},
 };
 use tuirealm_derive::MockComponent;
 
-use crate::app::Message;
+use crate::app::{Message, UserEvent};
 
 #[derive(MockComponent, Default)]
 pub struct GlobalListener {
     component: Phantom,
 }
 
-impl Component<Message, NoUserEvent> for GlobalListener {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for GlobalListener {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         match event {
             Event::Keyboard(KeyEvent {
                 code: Key::Char('q'),
@@ -46,6 +46,11 @@ impl Component<Message, NoUserEvent> for GlobalListener {
                 modifiers: KeyModifiers::CONTROL,
             }) => Some(Message::ToggleSchema),
 
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('l'),
+                modifiers: KeyModifiers::CONTROL,
+            }) => Some(Message::ToggleQuestionList),
+
             Event::Keyboard(KeyEvent {
                 code: Key::Char('r'),
                 modifiers: KeyModifiers::CONTROL,
@@ -56,6 +61,15 @@ impl Component<Message, NoUserEvent> for GlobalListener {
                 modifiers: KeyModifiers::CONTROL,
             }) => Some(Message::Submit),
 
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                modifiers: KeyModifiers::CONTROL,
+            }) => Some(Message::Skip),
+
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => Some(Message::Cancel),
+
+            Event::User(UserEvent::QueryFinished(id)) => Some(Message::QueryFinished(id)),
+
             _ => None,
         }
     }
@@ -1,12 +1,12 @@
 use tui_realm_stdlib::Table;
 use tuirealm::{
-    Component, Event, MockComponent, NoUserEvent,
+    Component, Event, MockComponent,
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent},
     props::{Alignment, BorderSides, Borders, Color, Style, TableBuilder, TextSpan},
 };
 
-use crate::app::Message;
+use crate::app::{Message, UserEvent};
 
 #[derive(MockComponent)]
 pub struct Help {
@@ -45,24 +45,39 @@ impl Default for Help {
                         .add_col(TextSpan::new("Shift + Tab"))
                         .add_col(TextSpan::new("Focus previous component"))
                         .add_row()
+                        .add_col(TextSpan::new("Up / Down"))
+                        .add_col(TextSpan::new("Select the pack to play"))
+                        .add_row()
+                        .add_col(TextSpan::new("Enter"))
+                        .add_col(TextSpan::new("Continue as the selected player"))
+                        .add_row()
                         .add_row()
                         .add_col(TextSpan::new("Ctrl + t"))
                         .add_col(TextSpan::new("Show schema"))
                         .add_row()
+                        .add_col(TextSpan::new("Ctrl + l"))
+                        .add_col(TextSpan::new("Show question list (practice mode)"))
+                        .add_row()
                         .add_col(TextSpan::new("Ctrl + r"))
                         .add_col(TextSpan::new("Run current query"))
                         .add_row()
                         .add_col(TextSpan::new("Ctrl + s"))
                         .add_col(TextSpan::new("Submit current query"))
                         .add_row()
+                        .add_col(TextSpan::new("Ctrl + n"))
+                        .add_col(TextSpan::new("Skip question and come back later"))
+                        .add_row()
+                        .add_col(TextSpan::new("Esc"))
+                        .add_col(TextSpan::new("Cancel running query"))
+                        .add_row()
                         .build(),
                 ),
         }
     }
 }
 
-impl Component<Message, NoUserEvent> for Help {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for Help {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         let _ = match event {
             Event::Keyboard(KeyEvent {
                 code: Key::Down, ..
@@ -1,25 +1,35 @@
 pub mod editor;
+pub mod error_popup;
 pub mod global_listener;
 pub mod help;
+pub mod pack_list;
 pub mod query_error;
 pub mod question;
+pub mod question_list;
+pub mod result_diff;
 pub mod result_table;
 pub mod schema_view;
 pub mod score;
 pub mod score_table;
+pub mod spinner;
 pub mod textarea;
 pub mod timer;
 pub mod username_input;
 
 pub use editor::Editor;
+pub use error_popup::ErrorPopup;
 pub use global_listener::GlobalListener;
 pub use help::Help;
+pub use pack_list::PackList;
 pub use query_error::QueryError;
 pub use question::Question;
+pub use question_list::{QuestionEntry, QuestionList};
+pub use result_diff::ResultDiff;
 pub use result_table::ResultTable;
 pub use schema_view::SchemaView;
 pub use score::Score;
 pub use score_table::ScoreTable;
+pub use spinner::Spinner;
 pub use textarea::TextArea;
 pub use timer::Timer;
 pub use username_input::UsernameInput;
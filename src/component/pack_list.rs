@@ -0,0 +1,81 @@
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, MockComponent,
+    command::{Cmd, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderSides, Borders, Color, PropPayload, PropValue, Style, TextSpan},
+};
+
+use crate::{
+    app::{Message, UserEvent},
+    repository::pack::Pack,
+};
+
+/// Lets the player pick the pack the next game is played with, reporting
+/// every change so the scores shown follow the highlighted pack.
+#[derive(MockComponent)]
+pub struct PackList {
+    component: Table,
+}
+
+impl PackList {
+    pub fn new(packs: &[Pack], selected: usize) -> Self {
+        let rows = packs
+            .iter()
+            .map(|pack| {
+                vec![
+                    TextSpan::new(pack.title.as_str()),
+                    TextSpan::new(pack.mode.to_string()),
+                    TextSpan::new(pack.questions.len().to_string()),
+                    TextSpan::new(pack.author.as_deref().unwrap_or_default()),
+                    TextSpan::new(pack.version.as_deref().unwrap_or_default()),
+                ]
+            })
+            .collect();
+
+        let mut component = Table::default()
+            .borders(
+                Borders::default()
+                    .sides(BorderSides::all())
+                    .color(Color::Green),
+            )
+            .inactive(Style::reset())
+            .title("Packs", Alignment::Center)
+            .scroll(true)
+            .step(5)
+            .highlighted_color(Color::Cyan)
+            .row_height(1)
+            .headers(["Title", "Mode", "Questions", "Author", "Version"])
+            .widths(&[40, 15, 15, 20, 10])
+            .table(rows);
+        component.attr(
+            Attribute::Value,
+            AttrValue::Payload(PropPayload::One(PropValue::Usize(selected))),
+        );
+
+        Self { component }
+    }
+}
+
+impl Component<Message, UserEvent> for PackList {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
+        match event {
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            _ => return Some(Message::None),
+        };
+
+        let index = self.state().unwrap_one().unwrap_usize();
+        Some(Message::SelectPack(index))
+    }
+}
@@ -1,12 +1,12 @@
 use tui_realm_stdlib::Textarea;
 use tuirealm::{
-    Component, Event, MockComponent, NoUserEvent,
+    Component, Event, MockComponent,
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent},
     props::{Alignment, BorderSides, Borders, Color, Style, TextSpan},
 };
 
-use crate::app::Message;
+use crate::app::{Message, UserEvent};
 
 #[derive(MockComponent)]
 pub struct QueryError {
@@ -29,8 +29,8 @@ impl QueryError {
     }
 }
 
-impl Component<Message, NoUserEvent> for QueryError {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for QueryError {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         let _ = match event {
             Event::Keyboard(KeyEvent {
                 code: Key::Down, ..
@@ -1,12 +1,12 @@
 use tui_realm_stdlib::Textarea;
 use tuirealm::{
-    Component, Event, MockComponent, NoUserEvent,
+    Component, Event, MockComponent,
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent},
     props::{Alignment, BorderSides, Borders, Color, Style, TextSpan},
 };
 
-use crate::app::Message;
+use crate::app::{Message, UserEvent};
 
 #[derive(MockComponent)]
 pub struct Question {
@@ -14,7 +14,7 @@ pub struct Question {
 }
 
 impl Question {
-    pub fn new(question: String) -> Self {
+    pub fn new(question: String, progress: String) -> Self {
         Self {
             component: Textarea::default()
                 .borders(
@@ -23,14 +23,14 @@ impl Question {
                         .color(Color::Green),
                 )
                 .inactive(Style::reset())
-                .title("Question", Alignment::Center)
+                .title(format!("Question {progress}"), Alignment::Center)
                 .text_rows(question.lines().map(TextSpan::new)),
         }
     }
 }
 
-impl Component<Message, NoUserEvent> for Question {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for Question {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         let _ = match event {
             Event::Keyboard(KeyEvent {
                 code: Key::Down, ..
@@ -0,0 +1,101 @@
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, MockComponent,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderSides, Borders, Color, PropPayload, PropValue, Style, TextSpan},
+};
+
+use crate::app::{Message, UserEvent};
+
+pub struct QuestionEntry {
+    pub question: String,
+    pub is_solved: bool,
+}
+
+#[derive(MockComponent)]
+pub struct QuestionList {
+    component: Table,
+}
+
+impl QuestionList {
+    pub fn new(entries: Vec<QuestionEntry>, current: usize) -> Self {
+        let rows = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let summary = entry
+                    .question
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())
+                    .unwrap_or_default()
+                    .to_string();
+                let status = if entry.is_solved { "Solved" } else { "" };
+
+                vec![
+                    TextSpan::from((index + 1).to_string()),
+                    TextSpan::from(summary),
+                    TextSpan::from(status),
+                ]
+            })
+            .collect();
+
+        let mut component = Table::default()
+            .borders(
+                Borders::default()
+                    .sides(BorderSides::all())
+                    .color(Color::Green),
+            )
+            .inactive(Style::reset())
+            .title("Questions", Alignment::Center)
+            .scroll(true)
+            .step(5)
+            .highlighted_color(Color::Cyan)
+            .row_height(1)
+            .headers(["#", "Question", "Status"])
+            .widths(&[5, 80, 15])
+            .table(rows);
+        component.attr(
+            Attribute::Value,
+            AttrValue::Payload(PropPayload::One(PropValue::Usize(current))),
+        );
+
+        Self { component }
+    }
+}
+
+impl Component<Message, UserEvent> for QuestionList {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
+        let _ = match event {
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                let index = self.state().unwrap_one().unwrap_usize();
+
+                return Some(Message::GoTo(index));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            _ => CmdResult::None,
+        };
+        Some(Message::None)
+    }
+}
@@ -0,0 +1,124 @@
+use rusqlite::types::Value;
+use tui_realm_stdlib::Textarea;
+use tuirealm::{
+    Component, Event, MockComponent,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderSides, Borders, Color, Style, TextSpan},
+};
+
+use crate::{
+    app::{Message, UserEvent},
+    util::{diff::Diff, query::Row},
+};
+
+/// Maximum number of missing or extra rows listed.
+const MAX_ROWS: usize = 20;
+
+fn format_row(row: &Row) -> String {
+    row.iter()
+        .map(|value| match value {
+            Value::Null => "Null".to_string(),
+            Value::Integer(n) => n.to_string(),
+            Value::Real(n) => n.to_string(),
+            Value::Text(s) => s.clone(),
+            Value::Blob(items) => items.iter().map(|x| x.to_string()).collect(),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn push_rows(lines: &mut Vec<String>, title: &str, header: &[String], rows: &[Row]) {
+    if rows.is_empty() {
+        return;
+    }
+
+    lines.push(String::new());
+    lines.push(format!("{title} ({}):", rows.len()));
+    lines.push(format!("  {}", header.join(" | ")));
+    lines.extend(
+        rows.iter()
+            .take(MAX_ROWS)
+            .map(|row| format!("  {}", format_row(row))),
+    );
+
+    if rows.len() > MAX_ROWS {
+        lines.push(format!("  ... and {} more", rows.len() - MAX_ROWS));
+    }
+}
+
+fn describe(diff: &Diff) -> Vec<String> {
+    let mut lines = vec!["Incorrect answer".to_string()];
+
+    if !diff.missing_columns.is_empty() {
+        lines.push(format!(
+            "Missing columns: {}",
+            diff.missing_columns.join(", ")
+        ));
+    }
+    if !diff.extra_columns.is_empty() {
+        lines.push(format!(
+            "Unexpected columns: {}",
+            diff.extra_columns.join(", ")
+        ));
+    }
+    if diff.is_column_misordered {
+        lines.push("Columns are correct but in the wrong order".to_string());
+    }
+    if diff.expected_row_count != diff.actual_row_count {
+        lines.push(format!(
+            "Expected {} rows, got {}",
+            diff.expected_row_count, diff.actual_row_count
+        ));
+    }
+    if diff.is_misordered {
+        lines.push("Rows are correct but in the wrong order".to_string());
+    }
+
+    push_rows(&mut lines, "Missing rows", &diff.header, &diff.missing_rows);
+    push_rows(&mut lines, "Extra rows", &diff.header, &diff.extra_rows);
+
+    lines
+}
+
+#[derive(MockComponent)]
+pub struct ResultDiff {
+    component: Textarea,
+}
+
+impl ResultDiff {
+    pub fn new(diff: Diff) -> Self {
+        Self {
+            component: Textarea::default()
+                .borders(
+                    Borders::default()
+                        .sides(BorderSides::all())
+                        .color(Color::Yellow),
+                )
+                .inactive(Style::reset())
+                .title("Result", Alignment::Center)
+                .text_rows(describe(&diff).into_iter().map(TextSpan::new)),
+        }
+    }
+}
+
+impl Component<Message, UserEvent> for ResultDiff {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
+        let _ = match event {
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Scroll(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            _ => CmdResult::None,
+        };
+        Some(Message::None)
+    }
+}
@@ -1,13 +1,20 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+};
 use rusqlite::types::Value;
-use tui_realm_stdlib::Table;
+use tui_realm_stdlib::{Radio, Table};
 use tuirealm::{
-    Component, Event, MockComponent, NoUserEvent,
+    AttrValue, Attribute, Component, Event, MockComponent, State,
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent},
     props::{Alignment, BorderSides, Borders, Color, Style, TextSpan},
 };
 
-use crate::{app::Message, util::query::Row};
+use crate::{
+    app::{Message, UserEvent},
+    util::query::{Output, StatementResult},
+};
 
 fn into_text_span(value: Value) -> TextSpan {
     match value {
@@ -21,45 +28,153 @@ fn into_text_span(value: Value) -> TextSpan {
     }
 }
 
-#[derive(MockComponent)]
+type Tab = (Vec<String>, Vec<Vec<TextSpan>>);
+
+fn into_tab(output: Output) -> Tab {
+    match output {
+        Output::Rows((headers, rows)) => {
+            let rows = rows
+                .into_iter()
+                .map(|row| row.into_iter().map(into_text_span).collect())
+                .collect();
+
+            (headers, rows)
+        }
+        Output::Changes(count) => (
+            vec!["Rows affected".to_string()],
+            vec![vec![TextSpan::new(count.to_string())]],
+        ),
+    }
+}
+
+fn tab_label(index: usize, sql: &str) -> String {
+    let keyword = sql
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with("--"))
+        .and_then(|line| line.split_whitespace().next())
+        .unwrap_or_default()
+        .to_uppercase();
+
+    format!("{}. {keyword}", index + 1)
+}
+
+fn build_table(tab: Option<&Tab>) -> Table {
+    let table = Table::default()
+        .borders(
+            Borders::default()
+                .sides(BorderSides::all())
+                .color(Color::Green),
+        )
+        .inactive(Style::reset())
+        .title("Result", Alignment::Center)
+        .scroll(true)
+        .step(5)
+        .highlighted_color(Color::Cyan)
+        .row_height(1);
+
+    match tab {
+        Some((headers, rows)) => table.headers(headers.clone()).table(rows.clone()),
+        None => table,
+    }
+}
+
 pub struct ResultTable {
-    component: Table,
+    radio: Radio,
+    table: Table,
+    tabs: Vec<Tab>,
 }
 
 impl ResultTable {
-    pub fn new(data: Option<(Vec<String>, Vec<Row>)>) -> Self {
-        let component = Table::default()
-            .borders(
-                Borders::default()
-                    .sides(BorderSides::all())
-                    .color(Color::Green),
-            )
+    pub fn new(results: Vec<StatementResult>) -> Self {
+        let labels = results
+            .iter()
+            .enumerate()
+            .map(|(index, result)| tab_label(index, &result.sql))
+            .collect::<Vec<_>>();
+        let tabs = results
+            .into_iter()
+            .map(|result| into_tab(result.output))
+            .collect::<Vec<_>>();
+
+        // The last statement is the one that gets graded, so show it first.
+        let selected = tabs.len().saturating_sub(1);
+
+        let radio = Radio::default()
+            .borders(Borders::default().sides(BorderSides::all()))
+            .title("Statements", Alignment::Center)
+            .foreground(Color::Cyan)
             .inactive(Style::reset())
-            .title("Result", Alignment::Center)
-            .scroll(true)
-            .step(5)
-            .highlighted_color(Color::Cyan)
-            .row_height(1);
-
-        let component = match data {
-            Some((headers, rows)) => {
-                let rows = rows
-                    .into_iter()
-                    .map(|row| row.into_iter().map(into_text_span).collect())
-                    .collect();
-
-                component.headers(headers).table(rows)
-            }
-            None => component,
-        };
+            .rewind(true)
+            .choices(labels)
+            .value(selected);
+
+        Self {
+            radio,
+            table: build_table(tabs.get(selected)),
+            tabs,
+        }
+    }
 
-        Self { component }
+    fn select(&mut self, index: usize) {
+        let focus = self.table.query(Attribute::Focus);
+
+        self.table = build_table(self.tabs.get(index));
+        if let Some(focus) = focus {
+            self.table.attr(Attribute::Focus, focus);
+        }
+    }
+}
+
+impl MockComponent for ResultTable {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        if self.tabs.len() <= 1 {
+            self.table.view(frame, area);
+            return;
+        }
+
+        let chunks = Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).split(area);
+
+        self.radio.view(frame, chunks[0]);
+        self.table.view(frame, chunks[1]);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.table.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.table.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        self.radio.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        match cmd {
+            Cmd::Move(Direction::Left) | Cmd::Move(Direction::Right) => {
+                let result = self.radio.perform(cmd);
+
+                let selected_index = self.state().unwrap_one().unwrap_usize();
+                self.select(selected_index);
+
+                result
+            }
+            _ => self.table.perform(cmd),
+        }
     }
 }
 
-impl Component<Message, NoUserEvent> for ResultTable {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for ResultTable {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         let _ = match event {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
             Event::Keyboard(KeyEvent {
                 code: Key::Down, ..
             }) => self.perform(Cmd::Move(Direction::Down)),
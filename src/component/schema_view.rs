@@ -4,13 +4,16 @@ use ratatui::{
 };
 use tui_realm_stdlib::{Radio, Table};
 use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, NoUserEvent, State,
+    AttrValue, Attribute, Component, Event, MockComponent, State,
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent},
     props::{Alignment, BorderSides, Borders, Color, Style, TextSpan},
 };
 
-use crate::{app::Message, repository::question::TableInfo};
+use crate::{
+    app::{Message, UserEvent},
+    repository::question::TableInfo,
+};
 
 pub struct SchemaView {
     radio: Radio,
@@ -109,8 +112,8 @@ impl MockComponent for SchemaView {
     }
 }
 
-impl Component<Message, NoUserEvent> for SchemaView {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for SchemaView {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         let cmd = match event {
             Event::Keyboard(KeyEvent {
                 code: Key::Left | Key::BackTab,
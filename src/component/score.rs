@@ -1,10 +1,10 @@
 use tui_realm_stdlib::Paragraph;
 use tuirealm::{
-    Component, Event, MockComponent, NoUserEvent,
+    Component, Event, MockComponent,
     props::{Alignment, BorderSides, Borders, TextSpan},
 };
 
-use crate::app::Message;
+use crate::app::{Message, UserEvent};
 
 #[derive(MockComponent)]
 pub struct Score {
@@ -23,8 +23,8 @@ impl Score {
     }
 }
 
-impl Component<Message, NoUserEvent> for Score {
-    fn on(&mut self, _: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for Score {
+    fn on(&mut self, _: Event<UserEvent>) -> Option<Message> {
         Some(Message::None)
     }
 }
@@ -1,27 +1,34 @@
 use tui_realm_stdlib::Table;
 use tuirealm::{
-    Component, Event, MockComponent, NoUserEvent,
+    Component, Event, MockComponent,
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent},
     props::{Alignment, BorderSides, Borders, Color, Style, TextSpan},
 };
 
-use crate::{app::Message, repository::score::Score};
+use crate::{
+    app::{Message, UserEvent},
+    repository::score::Score,
+};
 
 #[derive(MockComponent)]
 pub struct ScoreTable {
     component: Table,
+    usernames: Vec<String>,
 }
 
 impl ScoreTable {
     pub fn new(scores: Vec<Score>) -> Self {
+        let usernames = scores.iter().map(|score| score.username.clone()).collect();
         let scores = scores
             .into_iter()
             .map(|score| {
                 vec![
                     TextSpan::from(score.username),
-                    TextSpan::from(score.score.to_string()),
-                    TextSpan::from(score.created_at.to_string()),
+                    TextSpan::from(score.best_score.to_string()),
+                    TextSpan::from(score.latest_score.to_string()),
+                    TextSpan::from(score.run_count.to_string()),
+                    TextSpan::from(score.last_played_at.to_string()),
                 ]
             })
             .collect();
@@ -39,15 +46,24 @@ impl ScoreTable {
                 .step(5)
                 .highlighted_color(Color::Cyan)
                 .row_height(1)
-                .headers(["Username", "Score", "Time"])
+                .headers(["Username", "Best", "Latest", "Runs", "Last played"])
                 .table(scores),
+            usernames,
         }
     }
 }
 
-impl Component<Message, NoUserEvent> for ScoreTable {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for ScoreTable {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         let _ = match event {
+            // Continue as the selected player.
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                let index = self.state().unwrap_one().unwrap_usize();
+
+                return self.usernames.get(index).cloned().map(Message::Start);
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::Down, ..
             }) => self.perform(Cmd::Move(Direction::Down)),
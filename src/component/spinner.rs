@@ -0,0 +1,54 @@
+use tui_realm_stdlib::Paragraph;
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, MockComponent,
+    props::{Alignment, BorderSides, Borders, Color, PropPayload, PropValue, TextSpan},
+};
+
+use crate::app::{Message, UserEvent};
+
+const FRAMES: [char; 8] = ['⣾', '⣽', '⣻', '⢿', '⡿', '⣟', '⣯', '⣷'];
+
+fn text(frame: usize) -> AttrValue {
+    AttrValue::Payload(PropPayload::Vec(vec![PropValue::TextSpan(TextSpan::new(
+        format!("{} Running query... (Esc to cancel)", FRAMES[frame]),
+    ))]))
+}
+
+#[derive(MockComponent)]
+pub struct Spinner {
+    component: Paragraph,
+    frame: usize,
+}
+
+impl Default for Spinner {
+    fn default() -> Self {
+        let mut component = Paragraph::default()
+            .borders(
+                Borders::default()
+                    .sides(BorderSides::all())
+                    .color(Color::Yellow),
+            )
+            .title("Result", Alignment::Center)
+            .alignment(Alignment::Center);
+        component.attr(Attribute::Text, text(0));
+
+        Self {
+            component,
+            frame: 0,
+        }
+    }
+}
+
+impl Component<Message, UserEvent> for Spinner {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
+        match event {
+            Event::Tick => {
+                self.frame = (self.frame + 1) % FRAMES.len();
+                self.attr(Attribute::Text, text(self.frame));
+
+                Some(Message::None)
+            }
+            _ => None,
+        }
+    }
+}
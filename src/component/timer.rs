@@ -3,12 +3,12 @@ use std::time::Duration;
 use ratatui::{Frame, layout::Rect};
 use tui_realm_stdlib::Paragraph;
 use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, NoUserEvent, State, StateValue,
+    AttrValue, Attribute, Component, Event, MockComponent, State, StateValue,
     command::{Cmd, CmdResult},
     props::{Alignment, BorderSides, Borders, PropPayload, PropValue, TextSpan},
 };
 
-use crate::app::Message;
+use crate::app::{Message, UserEvent};
 
 pub struct OwnStates {
     time_left: Duration,
@@ -77,8 +77,8 @@ impl MockComponent for Timer {
     }
 }
 
-impl Component<Message, NoUserEvent> for Timer {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for Timer {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         match event {
             Event::Tick => {
                 self.states.tick();
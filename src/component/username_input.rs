@@ -1,13 +1,13 @@
 use ratatui::style::{Color, Style};
 use tui_realm_stdlib::Input;
 use tuirealm::{
-    Component, Event, MockComponent, NoUserEvent, State, StateValue,
+    Component, Event, MockComponent, State, StateValue,
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent, KeyModifiers},
     props::{Alignment, BorderSides, Borders, InputType, TextModifiers},
 };
 
-use crate::{app::Message, repository};
+use crate::app::{Message, UserEvent};
 
 #[derive(MockComponent)]
 pub struct UsernameInput {
@@ -16,10 +16,9 @@ pub struct UsernameInput {
 
 impl Default for UsernameInput {
     fn default() -> Self {
-        let input_type = InputType::Custom(
-            |username| !username.is_empty() && repository::score::is_new_user(username).unwrap(),
-            |_, _| true,
-        );
+        // Existing names are accepted, so returning players continue their
+        // account.
+        let input_type = InputType::Custom(|username| !username.is_empty(), |_, _| true);
 
         Self {
             component: Input::default()
@@ -42,8 +41,8 @@ impl Default for UsernameInput {
     }
 }
 
-impl Component<Message, NoUserEvent> for UsernameInput {
-    fn on(&mut self, event: Event<NoUserEvent>) -> Option<Message> {
+impl Component<Message, UserEvent> for UsernameInput {
+    fn on(&mut self, event: Event<UserEvent>) -> Option<Message> {
         let cmd_result = match event {
             Event::Keyboard(KeyEvent {
                 code: Key::Left, ..
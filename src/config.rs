@@ -1,9 +1,13 @@
-use std::sync::LazyLock;
+use std::{
+    path::{Path, PathBuf},
+    sync::{LazyLock, OnceLock},
+};
 
+use clap::ValueEnum;
 use serde::Deserialize;
 use strum::Display;
 
-#[derive(Debug, PartialEq, Eq, Hash, Deserialize, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Display, ValueEnum)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Mode {
@@ -15,17 +19,123 @@ pub enum Mode {
 pub struct Config {
     pub game_duration: u64,
     pub tick_rate: u64,
-    pub mode: Mode,
     pub database_file: String,
+    /// Only offer packs of this mode.
+    #[serde(default)]
+    pub mode: Option<Mode>,
+    /// Pack files or directories of them, offered next to the built-in packs.
+    #[serde(default)]
+    pub packs: Vec<String>,
+}
+
+const APP_NAME: &str = "sql-race";
+
+const DEFAULTS: &str = include_str!("../config.toml");
+
+const SYSTEM_CONFIG_FILE: &str = "/etc/sql-race/config.toml";
+
+const ENV_PREFIX: &str = "SQL_RACE";
+
+/// Where the score database was kept before it moved to the data directory.
+const LEGACY_DATABASE_FILE: &str = "data.db";
+
+/// `$XDG_CONFIG_HOME/sql-race/config.toml`, or the platform equivalent.
+fn user_config_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_NAME).join("config.toml"))
+}
+
+/// `$XDG_DATA_HOME/sql-race/data.db`, or the platform equivalent.
+fn user_database_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_NAME).join("data.db"))
+}
+
+/// The user database file, or the one in the working directory when there is
+/// no data directory or when only the working directory has a database, so
+/// scores kept there before upgrading are not lost.
+fn default_database_file() -> String {
+    match user_database_file() {
+        Some(file) if file.exists() || !Path::new(LEGACY_DATABASE_FILE).exists() => {
+            file.to_string_lossy().into_owned()
+        }
+        _ => LEGACY_DATABASE_FILE.to_string(),
+    }
+}
+
+/// `$XDG_DATA_HOME/sql-race/packs`, or the platform equivalent.
+fn user_pack_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_NAME).join("packs"))
+}
+
+/// Values given on the command line, which take precedence over the config
+/// file.
+#[derive(Debug, Default)]
+pub struct Overrides {
+    pub config_file: Option<String>,
+    pub game_duration: Option<u64>,
+    pub mode: Option<Mode>,
+    pub database_file: Option<String>,
+}
+
+static LOADED: OnceLock<Config> = OnceLock::new();
+
+/// Loads the config that `CONFIG` gives access to. Must be called once,
+/// before `CONFIG` is first used.
+pub fn init(overrides: &Overrides) -> Result<(), config::ConfigError> {
+    let config = Config::new(overrides)?;
+    LOADED.set(config).expect("config is initialized only once");
+
+    Ok(())
 }
 
 impl Config {
-    pub fn new() -> Result<Self, config::ConfigError> {
-        config::Config::builder()
-            .add_source(config::File::with_name("config"))
+    /// Layers, from lowest to highest precedence: compiled-in defaults, the
+    /// system file, the user file, the file given with `--config`,
+    /// `SQL_RACE_*` environment variables and the other command-line flags.
+    pub fn new(overrides: &Overrides) -> Result<Self, config::ConfigError> {
+        let default_database_file = default_database_file();
+        let mut builder = config::Config::builder()
+            .set_default("database_file", default_database_file.as_str())?
+            .add_source(config::File::from_str(DEFAULTS, config::FileFormat::Toml))
+            .add_source(config::File::with_name(SYSTEM_CONFIG_FILE).required(false));
+
+        if let Some(user_pack_dir) = user_pack_dir().filter(|dir| dir.is_dir()) {
+            builder =
+                builder.set_default("packs", vec![user_pack_dir.to_string_lossy().into_owned()])?;
+        }
+        if let Some(user_config_file) = user_config_file() {
+            builder = builder.add_source(config::File::from(user_config_file).required(false));
+        }
+        if let Some(config_file) = &overrides.config_file {
+            builder = builder.add_source(config::File::with_name(config_file));
+        }
+
+        let config: Self = builder
+            .add_source(
+                config::Environment::with_prefix(ENV_PREFIX)
+                    .try_parsing(true)
+                    .list_separator(",")
+                    .with_list_parse_key("packs"),
+            )
+            .set_override_option("game_duration", overrides.game_duration)?
+            .set_override_option("mode", overrides.mode.map(|mode| mode.to_string()))?
+            .set_override_option("database_file", overrides.database_file.clone())?
             .build()?
-            .try_deserialize()
+            .try_deserialize()?;
+
+        if default_database_file == LEGACY_DATABASE_FILE
+            && config.database_file == LEGACY_DATABASE_FILE
+            && let Some(user_database_file) = user_database_file()
+        {
+            eprintln!(
+                "Using the score database {LEGACY_DATABASE_FILE} in the working directory. \
+                Move it to {} to keep your scores wherever sql-race is run from.",
+                user_database_file.display()
+            );
+        }
+
+        Ok(config)
     }
 }
 
-pub static CONFIG: LazyLock<Config> = LazyLock::new(|| Config::new().unwrap());
+pub static CONFIG: LazyLock<&Config> =
+    LazyLock::new(|| LOADED.get().expect("config is initialized at startup"));
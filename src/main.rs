@@ -1,15 +1,101 @@
 pub mod app;
+pub mod cli;
 pub mod component;
 pub mod config;
 pub mod repository;
 pub mod util;
 
+use std::{collections::HashMap, panic, process::ExitCode};
+
+use clap::Parser;
 use tuirealm::{PollStrategy, Update};
 
-use crate::app::App;
+use crate::{
+    app::App,
+    cli::{Cli, Command, PlayArgs, ValidatePackArgs},
+    config::CONFIG,
+    repository::{
+        Database,
+        pack::{self, Pack},
+        score::Score,
+    },
+    util::query::CancelToken,
+};
+
+/// The packs at `paths`, or the built-in and configured ones when none are
+/// given, leaving out those of another mode than the configured one.
+fn load_packs(paths: &[String]) -> Vec<Result<Pack, Vec<pack::Error>>> {
+    let mut results = if paths.is_empty() {
+        let mut results = pack::built_in();
+        results.extend(pack::load_each(&CONFIG.packs));
+        results
+    } else {
+        pack::load_each(paths)
+    };
+
+    if let Some(mode) = CONFIG.mode {
+        results.retain(|result| match result {
+            Ok(pack) => pack.mode == mode,
+            // A broken pack's mode is unknown, so it is always reported.
+            Err(_) => true,
+        });
+    }
+
+    results
+}
+
+/// Loads everything a game needs, collecting every problem instead of
+/// stopping at the first.
+fn startup(args: &PlayArgs) -> Result<(Vec<Pack>, Database), Vec<String>> {
+    let mut errors = Vec::new();
+
+    let packs = match pack::collect(load_packs(&args.packs)) {
+        Ok(packs) if packs.is_empty() => {
+            match CONFIG.mode {
+                Some(mode) => errors.push(format!("No {mode} packs to play")),
+                None => errors.push("No packs to play".to_string()),
+            }
+            None
+        }
+        Ok(packs) => Some(packs),
+        Err(pack_errors) => {
+            errors.extend(pack_errors.iter().map(ToString::to_string));
+            None
+        }
+    };
+    let database = Database::open(&CONFIG.database_file)
+        .map_err(|error| errors.push(error.to_string()))
+        .ok();
+
+    match (packs, database) {
+        (Some(packs), Some(database)) => Ok((packs, database)),
+        _ => Err(errors),
+    }
+}
+
+/// Restores the terminal before the default hook prints the panic, so the
+/// message is readable and the shell usable afterwards.
+fn install_panic_hook() {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        ratatui::restore();
+        hook(info);
+    }));
+}
+
+fn play(args: &PlayArgs) -> ExitCode {
+    let (packs, database) = match startup(args) {
+        Ok(loaded) => loaded,
+        Err(errors) => {
+            for error in errors {
+                eprintln!("{error}");
+            }
+            return ExitCode::FAILURE;
+        }
+    };
 
-fn main() {
-    let mut app = App::default();
+    install_panic_hook();
+    let mut app = App::new(packs, database);
 
     app.terminal.enter_alternate_screen().unwrap();
     app.terminal.enable_raw_mode().unwrap();
@@ -38,4 +124,141 @@ fn main() {
     app.terminal.leave_alternate_screen().unwrap();
     app.terminal.disable_raw_mode().unwrap();
     app.terminal.clear_screen().unwrap();
+
+    ExitCode::SUCCESS
+}
+
+fn print_scores(scores: &[Score]) {
+    let width = scores
+        .iter()
+        .map(|score| score.username.chars().count())
+        .chain(["Username".len()])
+        .max()
+        .unwrap_or_default();
+
+    println!(
+        "{:>4}  {:<width$}  {:>6}  {:>6}  {:>4}  Last played",
+        "Rank", "Username", "Best", "Latest", "Runs"
+    );
+    for (index, score) in scores.iter().enumerate() {
+        println!(
+            "{:>4}  {:<width$}  {:>6}  {:>6}  {:>4}  {}",
+            index + 1,
+            score.username,
+            score.best_score,
+            score.latest_score,
+            score.run_count,
+            score.last_played_at
+        );
+    }
+}
+
+/// Prints the standings of every pack that has been played, one table each.
+fn leaderboard() -> ExitCode {
+    // Packs that are no longer installed are shown by their id.
+    let titles = load_packs(&[])
+        .into_iter()
+        .flatten()
+        .map(|pack| (pack.id, pack.title))
+        .collect::<HashMap<_, _>>();
+
+    let leaderboards = Database::open(&CONFIG.database_file).and_then(|db| {
+        db.get_played_packs()?
+            .into_iter()
+            .map(|pack| Ok((db.get_scores(pack.as_deref())?, pack)))
+            .collect::<Result<Vec<_>, _>>()
+    });
+    let leaderboards = match leaderboards {
+        Ok(leaderboards) => leaderboards,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for (index, (scores, pack)) in leaderboards.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        match pack {
+            Some(pack) => println!("{}", titles.get(pack).unwrap_or(pack)),
+            None => println!("Runs from before packs were tracked"),
+        }
+        print_scores(scores);
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Returns whether every question of the pack is sound.
+fn validate(pack: &Pack, cancel: &CancelToken) -> bool {
+    print!("{}: {}", pack.source, pack.title);
+    if let Some(version) = &pack.version {
+        print!(" {version}");
+    }
+    if let Some(author) = &pack.author {
+        print!(" by {author}");
+    }
+    println!(" [{}]", pack.mode);
+
+    let mut failures = 0;
+    for question in &pack.questions {
+        let problems = question.validate(cancel);
+        if problems.is_empty() {
+            println!("  {}: ok", question.id);
+            continue;
+        }
+
+        failures += 1;
+        for problem in problems {
+            println!("  {}: {problem}", question.id);
+        }
+    }
+
+    if failures > 0 {
+        println!("  {failures} of {} questions failed", pack.questions.len());
+    } else {
+        println!("  {} questions OK", pack.questions.len());
+    }
+
+    failures == 0
+}
+
+fn validate_packs(args: &ValidatePackArgs) -> ExitCode {
+    let results = load_packs(&args.packs);
+
+    let cancel = CancelToken::default();
+    let mut is_valid = true;
+    for result in results {
+        match result {
+            Ok(pack) => is_valid &= validate(&pack, &cancel),
+            Err(errors) => {
+                is_valid = false;
+                for error in errors {
+                    println!("{error}");
+                }
+            }
+        }
+    }
+
+    if is_valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(error) = config::init(&cli.overrides()) {
+        eprintln!("Could not load config: {error}");
+        return ExitCode::FAILURE;
+    }
+
+    match cli.command {
+        Some(Command::Play(args)) => play(&args),
+        None => play(&PlayArgs::default()),
+        Some(Command::Leaderboard) => leaderboard(),
+        Some(Command::ValidatePack(args)) => validate_packs(&args),
+    }
 }
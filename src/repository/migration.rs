@@ -0,0 +1,131 @@
+use rusqlite::Connection;
+
+/// Every schema change ever made to the score database, oldest first. Append
+/// new migrations to the end and never edit one that has been released.
+///
+/// Databases created before migrations existed are at version 0 but may
+/// already contain some of these tables, hence the `IF NOT EXISTS`.
+const MIGRATIONS: &[&str] = &[
+    include_str!("../../migrations/0001_scores.sql"),
+    include_str!("../../migrations/0002_users_and_runs.sql"),
+    include_str!("../../migrations/0003_progress.sql"),
+    include_str!("../../migrations/0004_pack_identity.sql"),
+];
+
+/// Brings the database up to date, recording the applied version in
+/// `PRAGMA user_version`.
+pub fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = connection.transaction()?;
+
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", index + 1)?;
+
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(connection: &Connection) -> usize {
+        connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn migrates_a_baseline_scores_database() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE scores (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    username TEXT NOT NULL UNIQUE,
+                    score INT NOT NULL,
+                    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+                );
+                INSERT INTO scores (username, score) VALUES ('alice', 120), ('bob', 80);",
+            )
+            .unwrap();
+
+        migrate(&mut connection).unwrap();
+
+        assert_eq!(version(&connection), MIGRATIONS.len());
+
+        let runs: Vec<(String, i64)> = connection
+            .prepare(
+                "SELECT username, score FROM runs
+                JOIN users ON users.id = user_id
+                ORDER BY username",
+            )
+            .unwrap()
+            .query_map((), |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            runs,
+            vec![("alice".to_string(), 120), ("bob".to_string(), 80)]
+        );
+
+        let has_scores: bool = connection
+            .query_row(
+                "SELECT count(*) > 0 FROM sqlite_schema WHERE name = 'scores'",
+                (),
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!has_scores);
+    }
+
+    #[test]
+    fn moves_progress_to_the_practice_pack() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..3] {
+            connection.execute_batch(migration).unwrap();
+        }
+        connection
+            .execute_batch(
+                "PRAGMA user_version = 3;
+                INSERT INTO progress (username, question_id, is_solved)
+                VALUES ('carol', 'q1', 1);",
+            )
+            .unwrap();
+
+        migrate(&mut connection).unwrap();
+
+        let progress: (String, String, String, bool) = connection
+            .query_row(
+                "SELECT username, pack, question_id, is_solved FROM progress
+                JOIN users ON users.id = user_id",
+                (),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            progress,
+            (
+                "carol".to_string(),
+                "practice".to_string(),
+                "q1".to_string(),
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn migrating_twice_changes_nothing() {
+        let mut connection = Connection::open_in_memory().unwrap();
+
+        migrate(&mut connection).unwrap();
+        migrate(&mut connection).unwrap();
+
+        assert_eq!(version(&connection), MIGRATIONS.len());
+    }
+}
@@ -1,2 +1,77 @@
+mod migration;
+pub mod pack;
+pub mod progress;
 pub mod question;
 pub mod score;
+pub mod user;
+
+use std::{fmt::Display, fs, path::Path, time::Duration};
+
+use rusqlite::{Connection, ErrorCode};
+
+/// How long to wait for another process holding the database before giving
+/// up.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum Error {
+    Open(String, rusqlite::Error),
+    Busy,
+    Sqlite(rusqlite::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Open(path, error) => write!(f, "Could not open score database {path}: {error}"),
+            Error::Busy => write!(f, "The score database is in use by another process"),
+            Error::Sqlite(error) => write!(f, "Score database error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Open(_, error) | Error::Sqlite(error) => Some(error),
+            Error::Busy => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        match error.sqlite_error_code() {
+            Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => Error::Busy,
+            _ => Error::Sqlite(error),
+        }
+    }
+}
+
+/// The score database, kept open for the whole session.
+pub struct Database {
+    connection: Connection,
+}
+
+impl Database {
+    pub fn open(database_file: &str) -> Result<Self, Error> {
+        if let Some(parent) = Path::new(database_file).parent() {
+            // Opening reports the failure if the directory is still missing.
+            let _ = fs::create_dir_all(parent);
+        }
+
+        let open = || {
+            let mut connection = Connection::open(database_file)?;
+
+            connection.busy_timeout(BUSY_TIMEOUT)?;
+            connection.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+            migration::migrate(&mut connection)?;
+
+            Ok(connection)
+        };
+
+        open()
+            .map(|connection| Self { connection })
+            .map_err(|error| Error::Open(database_file.to_string(), error))
+    }
+}
@@ -0,0 +1,245 @@
+use std::{collections::HashSet, fmt::Display, fs, io, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    config::Mode,
+    repository::question::{self, Question, RawQuestion},
+};
+
+/// Packs compiled into the binary, so there is always something to play.
+const BUILT_IN: &[(&str, &str)] = &[
+    ("practice", include_str!("../../packs/practice.toml")),
+    ("challenge", include_str!("../../packs/challenge.toml")),
+];
+
+const EXTENSION: &str = "toml";
+
+#[derive(Debug, Deserialize)]
+struct RawPack {
+    id: String,
+    title: String,
+    author: Option<String>,
+    version: Option<String>,
+    mode: Mode,
+    #[serde(default, rename = "question")]
+    questions: Vec<RawQuestion>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pack {
+    /// Path the pack was loaded from, or `built-in:<name>`.
+    pub source: String,
+    /// Progress and scores are kept under this id, so they follow the pack
+    /// wherever it is loaded from.
+    pub id: String,
+    pub title: String,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    pub mode: Mode,
+    pub questions: Vec<Question>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    ReadDir(String, io::Error),
+    Load(String, config::ConfigError),
+    Empty(String),
+    InvalidId(String, String),
+    DuplicateId(String, String),
+    Question(String, question::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::ReadDir(path, error) => {
+                write!(f, "Could not read pack directory {path}: {error}")
+            }
+            Error::Load(source, error) => write!(f, "{source}: Could not load pack: {error}"),
+            Error::Empty(source) => write!(f, "{source}: Pack has no questions"),
+            Error::InvalidId(source, id) => write!(
+                f,
+                "{source}: Pack id \"{id}\" must be a slug of lowercase letters, digits and '-'"
+            ),
+            Error::DuplicateId(source, id) => {
+                write!(f, "{source}: Pack id \"{id}\" is used by another pack")
+            }
+            Error::Question(source, error) => write!(f, "{source}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ReadDir(_, error) => Some(error),
+            Error::Load(_, error) => Some(error),
+            Error::Empty(_) | Error::InvalidId(..) | Error::DuplicateId(..) => None,
+            Error::Question(_, error) => Some(error),
+        }
+    }
+}
+
+fn parse<S>(source: String, file: S) -> Result<Pack, Vec<Error>>
+where
+    S: config::Source + Send + Sync + 'static,
+{
+    let raw: RawPack = config::Config::builder()
+        .add_source(file)
+        .build()
+        .and_then(|x| x.try_deserialize())
+        .map_err(|error| vec![Error::Load(source.clone(), error)])?;
+
+    if !question::is_slug(&raw.id) {
+        return Err(vec![Error::InvalidId(source, raw.id)]);
+    }
+    if raw.questions.is_empty() {
+        return Err(vec![Error::Empty(source)]);
+    }
+
+    let questions = question::build(raw.questions).map_err(|errors| {
+        errors
+            .into_iter()
+            .map(|error| Error::Question(source.clone(), error))
+            .collect::<Vec<_>>()
+    })?;
+
+    Ok(Pack {
+        source,
+        id: raw.id,
+        title: raw.title,
+        author: raw.author,
+        version: raw.version,
+        mode: raw.mode,
+        questions,
+    })
+}
+
+pub fn built_in() -> Vec<Result<Pack, Vec<Error>>> {
+    BUILT_IN
+        .iter()
+        .map(|(name, contents)| {
+            parse(
+                format!("built-in:{name}"),
+                config::File::from_str(contents, config::FileFormat::Toml),
+            )
+        })
+        .collect()
+}
+
+/// Loads the pack file at `path`.
+pub fn load(path: &str) -> Result<Pack, Vec<Error>> {
+    parse(path.to_string(), config::File::with_name(path))
+}
+
+/// The pack file at `path`, or the `.toml` files of the directory at `path`
+/// sorted by name.
+fn pack_files(path: &str) -> Result<Vec<String>, Error> {
+    if !Path::new(path).is_dir() {
+        return Ok(vec![path.to_string()]);
+    }
+
+    let read_error = |error| Error::ReadDir(path.to_string(), error);
+
+    let mut files = fs::read_dir(path)
+        .map_err(read_error)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(read_error)?
+        .into_iter()
+        .filter(|file| file.is_file() && file.extension().is_some_and(|x| x == EXTENSION))
+        .map(|file| file.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    files.sort();
+
+    Ok(files)
+}
+
+/// Loads every pack in `paths`, which may be pack files or directories of
+/// them, keeping each pack's result apart.
+pub fn load_each(paths: &[String]) -> Vec<Result<Pack, Vec<Error>>> {
+    paths
+        .iter()
+        .flat_map(|path| match pack_files(path) {
+            Ok(files) => files.iter().map(|file| load(file)).collect(),
+            Err(error) => vec![Err(vec![error])],
+        })
+        .collect()
+}
+
+/// Every pack of `results`, or every error found while loading any of them.
+/// Two packs with the same id would share their progress and scores, so
+/// that is an error too.
+pub fn collect(results: Vec<Result<Pack, Vec<Error>>>) -> Result<Vec<Pack>, Vec<Error>> {
+    let mut packs = Vec::new();
+    let mut errors = Vec::new();
+    let mut ids = HashSet::new();
+
+    for result in results {
+        match result {
+            Ok(pack) if !ids.insert(pack.id.clone()) => {
+                errors.push(Error::DuplicateId(pack.source, pack.id));
+            }
+            Ok(pack) => packs.push(pack),
+            Err(pack_errors) => errors.extend(pack_errors),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(packs)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(id: &str) -> Result<Pack, Vec<Error>> {
+        let contents = format!(
+            "id = \"{id}\"
+            title = \"Test\"
+            mode = \"practice\"
+
+            [[question]]
+            id = \"one\"
+            question = \"Select one\"
+            answer = \"SELECT 1\"
+            schema = \"\""
+        );
+
+        parse(
+            format!("test:{id}"),
+            config::File::from_str(&contents, config::FileFormat::Toml),
+        )
+    }
+
+    #[test]
+    fn built_in_packs_have_distinct_ids() {
+        let packs = collect(built_in()).unwrap();
+
+        assert_eq!(
+            packs
+                .iter()
+                .map(|pack| pack.id.as_str())
+                .collect::<Vec<_>>(),
+            ["practice", "challenge"]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_and_duplicate_ids() {
+        assert!(matches!(
+            pack("Not a slug").unwrap_err().as_slice(),
+            [Error::InvalidId(_, _)]
+        ));
+
+        let errors = collect(vec![pack("same"), pack("other"), pack("same")]).unwrap_err();
+        assert!(matches!(
+            errors.as_slice(),
+            [Error::DuplicateId(_, id)] if id == "same"
+        ));
+    }
+}
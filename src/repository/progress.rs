@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use crate::repository::{Database, Error, user};
+
+/// How far a player got on one question, kept across sessions.
+#[derive(Debug, Default, Clone)]
+pub struct Progress {
+    pub question_id: String,
+    pub is_solved: bool,
+    pub attempts: u32,
+    pub best_query: Option<String>,
+    pub best_credit: f64,
+    pub time_spent: Duration,
+}
+
+impl Progress {
+    pub fn new(question_id: String) -> Self {
+        Self {
+            question_id,
+            ..Self::default()
+        }
+    }
+}
+
+impl<'a> TryFrom<&rusqlite::Row<'a>> for Progress {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row<'a>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            question_id: row.get("question_id")?,
+            is_solved: row.get("is_solved")?,
+            attempts: row.get("attempts")?,
+            best_query: row.get("best_query")?,
+            best_credit: row.get("best_credit")?,
+            time_spent: Duration::from_secs(row.get("time_spent")?),
+        })
+    }
+}
+
+impl Database {
+    /// Progress of `username` on the questions of `pack`.
+    pub fn get_progress(&self, username: &str, pack: &str) -> Result<Vec<Progress>, Error> {
+        let mut stmt = self.connection.prepare_cached(
+            "SELECT progress.* FROM progress
+            JOIN users ON users.id = progress.user_id
+            WHERE users.username = ? AND progress.pack = ?",
+        )?;
+
+        let progress = stmt
+            .query_and_then([username, pack], |row| Progress::try_from(row))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(progress)
+    }
+
+    pub fn save_progress(
+        &self,
+        username: &str,
+        pack: &str,
+        progress: &Progress,
+    ) -> Result<(), Error> {
+        let user_id = user::get_or_create(&self.connection, username)?;
+
+        self.connection
+            .prepare_cached(
+                "INSERT INTO progress
+                    (user_id, pack, question_id, is_solved, attempts,
+                    best_query, best_credit, time_spent)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (user_id, pack, question_id) DO UPDATE SET
+                    is_solved = excluded.is_solved,
+                    attempts = excluded.attempts,
+                    best_query = excluded.best_query,
+                    best_credit = excluded.best_credit,
+                    time_spent = excluded.time_spent,
+                    updated_at = current_timestamp",
+            )?
+            .execute((
+                user_id,
+                pack,
+                &progress.question_id,
+                progress.is_solved,
+                progress.attempts,
+                &progress.best_query,
+                progress.best_credit,
+                progress.time_spent.as_secs(),
+            ))?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,14 @@
-use std::collections::HashMap;
+use std::{collections::HashSet, fmt::Display};
 
 use rusqlite::Connection;
 use serde::Deserialize;
 
-use crate::config::{CONFIG, Mode};
+use crate::util::{
+    diff::{self, Diff},
+    query::{self, Access, CancelToken, Grading},
+    scoring::Difficulty,
+    snapshot::Snapshot,
+};
 
 #[derive(Debug, Clone)]
 pub struct Column {
@@ -39,17 +44,17 @@ impl TableInfo {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Schema {
-    pub raw: String,
     pub table_infos: Vec<TableInfo>,
+    pub snapshot: Snapshot,
 }
 
 impl Schema {
-    pub fn new(raw: String) -> rusqlite::Result<Self> {
+    pub fn new(raw: &str) -> rusqlite::Result<Self> {
         let conn = Connection::open_in_memory()?;
 
-        conn.execute_batch(&raw)?;
+        conn.execute_batch(raw)?;
 
         let mut stmt = conn.prepare("SELECT name FROM sqlite_schema WHERE type = 'table'")?;
         let table_infos = stmt
@@ -58,53 +63,410 @@ impl Schema {
                     .and_then(|name| TableInfo::new(name, &conn))
             })?
             .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        let snapshot = Snapshot::from_connection(&conn)?;
 
-        Ok(Self { raw, table_infos })
+        Ok(Self {
+            table_infos,
+            snapshot,
+        })
     }
 }
 
+/// How an answer is graded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// Compare the rows returned by the player's query.
+    #[default]
+    Result,
+    /// Compare the database after running the player's statements.
+    State,
+}
+
 #[derive(Debug, Deserialize)]
-struct RawQuestion {
+pub(super) struct RawQuestion {
+    id: String,
     question: String,
     answer: String,
     schema: String,
+    #[serde(default)]
+    grading: Grading,
+    #[serde(default)]
+    hidden_datasets: Vec<String>,
+    #[serde(default)]
+    access: Access,
+    #[serde(default)]
+    kind: Kind,
+    #[serde(default)]
+    verify: Vec<String>,
+    #[serde(default)]
+    difficulty: Difficulty,
+    points: Option<u64>,
 }
 
-type QuestionPack = HashMap<Mode, Vec<RawQuestion>>;
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Question {
+    /// Slug identifying the question across sessions, regardless of its
+    /// position in the pack.
+    pub id: String,
     pub question: String,
     pub answer: String,
     pub schema: Schema,
+    pub grading: Grading,
+    pub hidden_datasets: Vec<Snapshot>,
+    pub access: Access,
+    pub kind: Kind,
+    pub verify: Vec<String>,
+    pub difficulty: Difficulty,
+    pub points: u64,
+}
+
+impl Question {
+    /// Every seeded database the answer is graded against, starting with the
+    /// one shown to the player.
+    pub fn datasets(&self) -> impl Iterator<Item = &Snapshot> {
+        std::iter::once(&self.schema.snapshot).chain(&self.hidden_datasets)
+    }
+
+    /// Grades `query` against every dataset of the question.
+    pub fn check(&self, query: &str, cancel: &CancelToken) -> Result<bool, query::Error> {
+        self.datasets()
+            .map(|database| match self.kind {
+                Kind::Result => query::is_equal(
+                    query,
+                    &self.answer,
+                    database,
+                    &self.grading,
+                    self.access,
+                    cancel,
+                ),
+                Kind::State => query::is_state_equal(
+                    query,
+                    &self.answer,
+                    database,
+                    &self.verify,
+                    &self.grading,
+                    self.access,
+                    cancel,
+                ),
+            })
+            .find(|result| !matches!(result, Ok(true)))
+            .unwrap_or(Ok(true))
+    }
+
+    /// Runs the answer against every dataset and reports what would make the
+    /// question impossible or unfair to pass.
+    pub fn validate(&self, cancel: &CancelToken) -> Vec<Problem> {
+        let mut problems = Vec::new();
+
+        for (index, database) in self.datasets().enumerate() {
+            let hidden_dataset = index.checked_sub(1);
+            let is_empty = match self.kind {
+                Kind::Result => query::run(&self.answer, database, self.access, cancel)
+                    .map(|(_, rows)| rows.is_empty()),
+                Kind::State => {
+                    query::run_script(&self.answer, database, &self.verify, self.access, cancel)
+                        .map(|_| false)
+                }
+            };
+
+            match is_empty {
+                // Hidden datasets may leave nothing to return on purpose.
+                Ok(true) if hidden_dataset.is_none() => problems.push(Problem::NoRows),
+                Ok(_) => {}
+                Err(error) => problems.push(Problem::Query(hidden_dataset, error)),
+            }
+        }
+
+        if self.kind == Kind::Result
+            && query::is_limited(&self.answer)
+            && !query::is_ordered(&self.answer)
+        {
+            problems.push(Problem::Unordered);
+        }
+
+        if problems.is_empty() {
+            match self.check(&self.answer, cancel) {
+                Ok(true) => {}
+                Ok(false) => problems.push(Problem::Mismatch),
+                Err(error) => problems.push(Problem::Query(None, error)),
+            }
+        }
+
+        problems
+    }
+
+    /// Explains how the result of `query` differs from the expected one on
+    /// every dataset, starting with the one shown to the player.
+    pub fn diffs(&self, query: &str, cancel: &CancelToken) -> Result<Vec<Diff>, query::Error> {
+        self.datasets()
+            .map(|database| {
+                diff::diff(
+                    query,
+                    &self.answer,
+                    database,
+                    &self.grading,
+                    self.access,
+                    cancel,
+                )
+            })
+            .collect()
+    }
 }
 
 impl TryFrom<RawQuestion> for Question {
     type Error = rusqlite::Error;
 
     fn try_from(raw: RawQuestion) -> Result<Self, Self::Error> {
-        let schema = Schema::new(raw.schema)?;
+        let schema = Schema::new(&raw.schema)?;
+
+        // Hidden datasets reuse the schema's DDL but replace all of its rows.
+        let clear_tables = schema
+            .table_infos
+            .iter()
+            .map(|table| format!("DELETE FROM \"{}\";\n", table.name.replace('"', "\"\"")))
+            .collect::<String>();
+        let hidden_datasets = raw
+            .hidden_datasets
+            .into_iter()
+            .map(|dataset| {
+                let conn = schema.snapshot.restore()?;
+                conn.execute_batch(&clear_tables)?;
+                conn.execute_batch(&dataset)?;
+
+                Snapshot::from_connection(&conn)
+            })
+            .collect::<Result<_, _>>()?;
 
         Ok(Self {
+            id: raw.id,
             question: raw.question,
             answer: raw.answer,
             schema,
+            grading: raw.grading,
+            hidden_datasets,
+            access: raw.access,
+            kind: raw.kind,
+            verify: raw.verify,
+            difficulty: raw.difficulty,
+            points: raw.points.unwrap_or(raw.difficulty.points()),
         })
     }
 }
 
-const PREFIX: &str = "question";
+#[derive(Debug)]
+pub enum Error {
+    Sqlite(String, rusqlite::Error),
+    InvalidId(String),
+    DuplicateId(String),
+}
 
-pub fn get_all() -> rusqlite::Result<Vec<Question>> {
-    let question_file = format!("{PREFIX}.{}", CONFIG.mode);
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // rusqlite repeats the whole script, which buries the message.
+            Error::Sqlite(id, rusqlite::Error::SqlInputError { msg, .. }) => {
+                write!(f, "Question \"{id}\" has invalid SQL: {msg}")
+            }
+            Error::Sqlite(id, error) => write!(f, "Question \"{id}\" is invalid: {error}"),
+            Error::InvalidId(id) => write!(
+                f,
+                "Question id \"{id}\" must be a non-empty slug of lowercase letters, digits and '-'"
+            ),
+            Error::DuplicateId(id) => write!(f, "Question id \"{id}\" is used more than once"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Sqlite(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
 
-    let mut question_pack: QuestionPack = config::Config::builder()
-        .add_source(config::File::with_name(&question_file))
-        .build()
-        .and_then(|x| x.try_deserialize())
+/// Something wrong with a question's answer, found by `Question::validate`.
+#[derive(Debug)]
+pub enum Problem {
+    /// The answer fails on the shown dataset, or on the given hidden one.
+    Query(Option<usize>, query::Error),
+    NoRows,
+    /// `LIMIT` without `ORDER BY` keeps arbitrary rows.
+    Unordered,
+    Mismatch,
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::Query(None, error) => write!(f, "answer fails: {error}"),
+            Problem::Query(Some(index), error) => {
+                write!(f, "answer fails on hidden dataset {}: {error}", index + 1)
+            }
+            Problem::NoRows => write!(f, "answer returns no rows"),
+            Problem::Unordered => write!(
+                f,
+                "answer uses LIMIT without ORDER BY, so the rows it returns are arbitrary"
+            ),
+            Problem::Mismatch => write!(f, "answer does not match itself"),
+        }
+    }
+}
+
+pub(super) fn is_slug(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Ensures every question has a well-formed id that no other question uses.
+fn validate_ids(questions: &[RawQuestion]) -> Vec<Error> {
+    let mut ids = HashSet::new();
+    let mut errors = Vec::new();
+
+    for question in questions {
+        if !is_slug(&question.id) {
+            errors.push(Error::InvalidId(question.id.clone()));
+        } else if !ids.insert(question.id.as_str()) {
+            errors.push(Error::DuplicateId(question.id.clone()));
+        }
+    }
+
+    errors
+}
+
+/// Builds the questions of a pack, reporting every invalid question rather
+/// than only the first.
+pub(super) fn build(questions: Vec<RawQuestion>) -> Result<Vec<Question>, Vec<Error>> {
+    let mut errors = validate_ids(&questions);
+
+    let questions = questions
+        .into_iter()
+        .filter_map(|raw| {
+            let id = raw.id.clone();
+            Question::try_from(raw)
+                .map_err(|error| errors.push(Error::Sqlite(id, error)))
+                .ok()
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(questions)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STUDENTS: &str = "CREATE TABLE students (id INTEGER PRIMARY KEY, name TEXT, score INT);
+        INSERT INTO students VALUES (1, 'Ann', 90), (2, 'Bob', 40), (3, 'Cid', 70);";
+
+    fn raw(schema: &str, answer: &str) -> RawQuestion {
+        RawQuestion {
+            id: "test".to_string(),
+            question: String::new(),
+            answer: answer.to_string(),
+            schema: schema.to_string(),
+            grading: Grading::default(),
+            hidden_datasets: Vec::new(),
+            access: Access::default(),
+            kind: Kind::default(),
+            verify: Vec::new(),
+            difficulty: Difficulty::default(),
+            points: None,
+        }
+    }
+
+    fn check(question: &Question, query: &str) -> bool {
+        question.check(query, &CancelToken::default()).unwrap()
+    }
+
+    #[test]
+    fn rows_are_compared_in_any_order_unless_the_answer_sorts_them() {
+        let unordered =
+            Question::try_from(raw(STUDENTS, "SELECT name FROM students WHERE score > 50"))
+                .unwrap();
+        let ordered = Question::try_from(raw(
+            STUDENTS,
+            "SELECT name FROM students WHERE score > 50 ORDER BY name",
+        ))
+        .unwrap();
+
+        let reversed = "SELECT name FROM students WHERE score > 50 ORDER BY name DESC";
+        assert!(check(&unordered, reversed));
+        assert!(!check(&ordered, reversed));
+        assert!(check(
+            &ordered,
+            "SELECT name FROM students WHERE score >= 70 ORDER BY 1"
+        ));
+        assert!(!check(&unordered, "SELECT name FROM students"));
+    }
+
+    #[test]
+    fn hard_coded_answers_fail_on_hidden_datasets() {
+        let question = Question::try_from(RawQuestion {
+            hidden_datasets: vec![
+                "INSERT INTO students VALUES (4, 'Dee', 80), (5, 'Eve', 10);".to_string(),
+            ],
+            ..raw(STUDENTS, "SELECT name FROM students WHERE score > 50")
+        })
+        .unwrap();
+
+        assert!(check(
+            &question,
+            "SELECT name FROM students WHERE score > 60"
+        ));
+        assert!(!check(
+            &question,
+            "SELECT name FROM students WHERE id IN (1, 3)"
+        ));
+    }
+
+    #[test]
+    fn hidden_datasets_clear_tables_with_quoted_names() {
+        let question = Question::try_from(RawQuestion {
+            hidden_datasets: vec!["INSERT INTO \"odd\"\"name\" VALUES (2);".to_string()],
+            ..raw(
+                "CREATE TABLE \"odd\"\"name\" (x INT); INSERT INTO \"odd\"\"name\" VALUES (1);",
+                "SELECT x FROM \"odd\"\"name\"",
+            )
+        })
         .unwrap();
 
-    let questions = question_pack.remove(&CONFIG.mode).unwrap();
+        assert!(!check(&question, "SELECT 1 AS x"));
+    }
 
-    questions.into_iter().map(Question::try_from).collect()
+    #[test]
+    fn state_questions_compare_the_resulting_database() {
+        let delete = || RawQuestion {
+            access: Access::Write,
+            kind: Kind::State,
+            ..raw(STUDENTS, "DELETE FROM students WHERE score < 50")
+        };
+        let dumped = Question::try_from(delete()).unwrap();
+        let counted = Question::try_from(RawQuestion {
+            verify: vec!["SELECT count(*) FROM students".to_string()],
+            ..delete()
+        })
+        .unwrap();
+
+        let correct = "DELETE FROM students WHERE name = 'Bob'";
+        let wrong = "DELETE FROM students WHERE id = 3";
+        assert!(check(&dumped, correct));
+        assert!(!check(&dumped, wrong));
+        assert!(!check(&dumped, "SELECT * FROM students"));
+
+        // Only what the verification queries observe is graded.
+        assert!(check(&counted, correct));
+        assert!(check(&counted, wrong));
+        assert!(!check(&counted, "DELETE FROM students"));
+    }
 }
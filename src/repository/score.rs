@@ -1,15 +1,15 @@
-use std::fs;
-
 use chrono::NaiveDateTime;
-use rusqlite::{Connection, OptionalExtension};
 
-use crate::config::CONFIG;
+use crate::repository::{Database, Error, user};
 
+/// A player's standing on the leaderboard, summarizing all of their runs.
 #[derive(Debug)]
 pub struct Score {
     pub username: String,
-    pub score: u64,
-    pub created_at: NaiveDateTime,
+    pub best_score: u64,
+    pub latest_score: u64,
+    pub run_count: u64,
+    pub last_played_at: NaiveDateTime,
 }
 
 impl<'a> TryFrom<&rusqlite::Row<'a>> for Score {
@@ -18,57 +18,85 @@ impl<'a> TryFrom<&rusqlite::Row<'a>> for Score {
     fn try_from(row: &rusqlite::Row<'a>) -> Result<Self, Self::Error> {
         Ok(Self {
             username: row.get("username")?,
-            score: row.get("score")?,
-            created_at: row.get("created_at")?,
+            best_score: row.get("best_score")?,
+            latest_score: row.get("latest_score")?,
+            run_count: row.get("run_count")?,
+            last_played_at: row.get("last_played_at")?,
         })
     }
 }
 
-const SCHEMA: &str = include_str!("../../schema.sql");
-
-fn new_connection(database_file: &str) -> rusqlite::Result<Connection> {
-    let existed = fs::exists(database_file).unwrap();
-
-    let connection = rusqlite::Connection::open(database_file)?;
-    if !existed {
-        connection.execute_batch(SCHEMA)?;
+impl Database {
+    /// Records a finished run of `pack` along with the fraction of each
+    /// question's points the player earned.
+    pub fn insert_run(
+        &mut self,
+        username: &str,
+        pack: &str,
+        score: u64,
+        credits: &[(&str, f64)],
+    ) -> Result<(), Error> {
+        let transaction = self.connection.transaction()?;
+
+        let user_id = user::get_or_create(&transaction, username)?;
+        transaction.execute(
+            "INSERT INTO runs (user_id, pack, score) VALUES (?, ?, ?)",
+            (user_id, pack, score),
+        )?;
+        let run_id = transaction.last_insert_rowid();
+
+        {
+            let mut stmt = transaction.prepare(
+                "INSERT INTO credits (run_id, pack, question_id, credit) VALUES (?, ?, ?, ?)",
+            )?;
+            for (question_id, credit) in credits {
+                stmt.execute((run_id, pack, question_id, credit))?;
+            }
+        }
+
+        Ok(transaction.commit()?)
     }
 
-    Ok(connection)
-}
-
-pub fn is_new_user(username: &str) -> rusqlite::Result<bool> {
-    let connection = new_connection(&CONFIG.database_file)?;
-
-    match connection
-        .query_row(
-            "SELECT id FROM scores WHERE username = ?",
-            [username],
-            |_| Ok(()),
-        )
-        .optional()
-    {
-        Ok(None) => Ok(true),
-        Ok(Some(_)) | Err(_) => Ok(false),
+    /// Standings on `pack`, or on runs recorded before packs were tracked
+    /// when `pack` is `None`.
+    pub fn get_scores(&self, pack: Option<&str>) -> Result<Vec<Score>, Error> {
+        let mut stmt = self.connection.prepare_cached(
+            "SELECT
+                users.username,
+                MAX(runs.score) AS best_score,
+                (
+                    SELECT latest.score FROM runs AS latest
+                    WHERE latest.user_id = users.id AND latest.pack IS ?1
+                    ORDER BY latest.id DESC
+                    LIMIT 1
+                ) AS latest_score,
+                COUNT(*) AS run_count,
+                MAX(runs.created_at) AS last_played_at
+            FROM users
+            JOIN runs ON runs.user_id = users.id
+            WHERE runs.pack IS ?1
+            GROUP BY users.id
+            ORDER BY best_score DESC",
+        )?;
+
+        let scores = stmt
+            .query_and_then([pack], |row| Score::try_from(row))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(scores)
     }
-}
-
-pub fn insert(username: &str, score: u64) -> rusqlite::Result<()> {
-    let connection = new_connection(&CONFIG.database_file)?;
-
-    connection.execute(
-        "INSERT INTO scores (username, score) VALUES (?, ?)",
-        (username, score),
-    )?;
 
-    Ok(())
-}
-
-pub fn get_all() -> rusqlite::Result<Vec<Score>> {
-    let connection = new_connection(&CONFIG.database_file)?;
+    /// Every pack with at least one run, `None` standing for runs recorded
+    /// before packs were tracked.
+    pub fn get_played_packs(&self) -> Result<Vec<Option<String>>, Error> {
+        let mut stmt = self
+            .connection
+            .prepare_cached("SELECT DISTINCT pack FROM runs ORDER BY pack")?;
 
-    let mut stmt = connection.prepare("SELECT * FROM scores ORDER BY score DESC")?;
+        let packs = stmt
+            .query_map((), |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
 
-    stmt.query_and_then((), |row| Score::try_from(row))?
-        .collect()
+        Ok(packs)
+    }
 }
@@ -0,0 +1,15 @@
+use rusqlite::Connection;
+
+/// Id of the user named `username`, creating them on their first run.
+pub(super) fn get_or_create(connection: &Connection, username: &str) -> rusqlite::Result<i64> {
+    connection.execute(
+        "INSERT INTO users (username) VALUES (?) ON CONFLICT (username) DO NOTHING",
+        [username],
+    )?;
+
+    connection.query_row(
+        "SELECT id FROM users WHERE username = ?",
+        [username],
+        |row| row.get(0),
+    )
+}
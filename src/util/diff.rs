@@ -0,0 +1,238 @@
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::util::{
+    query::{
+        self, Access, CancelToken, Error, Grading, ResultSet, Row, align_rows, compare_row,
+        is_row_equal, subtract_rows,
+    },
+    snapshot::Snapshot,
+};
+
+/// Explains how a player's result differs from the reference result, without
+/// exposing the reference query itself.
+#[derive(Debug, Default)]
+pub struct Diff {
+    pub header: Vec<String>,
+    pub missing_columns: Vec<String>,
+    pub extra_columns: Vec<String>,
+    pub expected_row_count: usize,
+    pub actual_row_count: usize,
+    pub missing_rows: Vec<Row>,
+    pub extra_rows: Vec<Row>,
+    pub is_misordered: bool,
+    /// The columns are the expected ones but in another order than the
+    /// grading policy allows, so the rows could not be compared.
+    pub is_column_misordered: bool,
+}
+
+/// Elements of `first` without a counterpart in `second`, both given as
+/// multisets.
+fn subtract<T: PartialEq + Clone>(first: &[T], second: &[T]) -> Vec<T> {
+    let mut used = vec![false; second.len()];
+
+    first
+        .iter()
+        .filter(
+            |item| match (0..second.len()).find(|&i| !used[i] && &second[i] == *item) {
+                Some(index) => {
+                    used[index] = true;
+                    false
+                }
+                None => true,
+            },
+        )
+        .cloned()
+        .collect()
+}
+
+impl Diff {
+    pub fn new(
+        actual: ResultSet,
+        expected: ResultSet,
+        is_ordered: bool,
+        grading: &Grading,
+    ) -> Self {
+        let mut diff = Self {
+            header: expected.0.clone(),
+            missing_columns: subtract(&expected.0, &actual.0),
+            extra_columns: subtract(&actual.0, &expected.0),
+            expected_row_count: expected.1.len(),
+            actual_row_count: actual.1.len(),
+            ..Self::default()
+        };
+
+        let Some((actual_rows, expected_rows)) = align_rows(actual, expected, grading) else {
+            diff.is_column_misordered =
+                diff.missing_columns.is_empty() && diff.extra_columns.is_empty();
+            return diff;
+        };
+
+        // Column names may legitimately differ under the grading policy.
+        diff.missing_columns.clear();
+        diff.extra_columns.clear();
+
+        let mut sorted_actual = actual_rows.clone();
+        let mut sorted_expected = expected_rows.clone();
+        sorted_actual.sort_by(compare_row);
+        sorted_expected.sort_by(compare_row);
+
+        diff.extra_rows = subtract_rows(&sorted_actual, &sorted_expected);
+        diff.missing_rows = subtract_rows(&sorted_expected, &sorted_actual);
+
+        diff.is_misordered = is_ordered
+            && diff.missing_rows.is_empty()
+            && diff.extra_rows.is_empty()
+            && !actual_rows
+                .iter()
+                .zip(&expected_rows)
+                .all(|(a, b)| is_row_equal(a, b));
+
+        diff
+    }
+
+    /// Whether the two results are indistinguishable, which happens when the
+    /// answer only fails on a hidden dataset.
+    pub fn is_empty(&self) -> bool {
+        self.missing_columns.is_empty()
+            && self.extra_columns.is_empty()
+            && self.expected_row_count == self.actual_row_count
+            && self.missing_rows.is_empty()
+            && self.extra_rows.is_empty()
+            && !self.is_misordered
+            && !self.is_column_misordered
+    }
+}
+
+/// Runs `query` and `answer` on `database` and explains how their results
+/// differ.
+pub fn diff(
+    query: &str,
+    answer: &str,
+    database: &Snapshot,
+    grading: &Grading,
+    access: Access,
+    cancel: &CancelToken,
+) -> Result<Diff, Error> {
+    let [actual, expected] = [query, answer]
+        .par_iter()
+        .map(|&query| query::run(query, database, access, cancel))
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .unwrap();
+
+    Ok(Diff::new(
+        actual,
+        expected,
+        query::is_ordered(answer),
+        grading,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::types::Value;
+
+    use super::*;
+    use crate::util::query::ColumnPolicy;
+
+    fn result(names: &[&str], rows: &[&[i64]]) -> ResultSet {
+        (
+            names.iter().map(|name| name.to_string()).collect(),
+            rows.iter()
+                .map(|row| row.iter().map(|&n| Value::Integer(n)).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn equal_results_have_an_empty_diff() {
+        let diff = Diff::new(
+            result(&["a"], &[&[2], &[1]]),
+            result(&["a"], &[&[1], &[2]]),
+            false,
+            &Grading::default(),
+        );
+
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn reports_missing_and_extra_rows() {
+        let diff = Diff::new(
+            result(&["a"], &[&[1], &[3], &[3]]),
+            result(&["a"], &[&[1], &[2], &[3]]),
+            false,
+            &Grading::default(),
+        );
+
+        assert_eq!(diff.missing_rows, vec![vec![Value::Integer(2)]]);
+        assert_eq!(diff.extra_rows, vec![vec![Value::Integer(3)]]);
+        assert_eq!((diff.expected_row_count, diff.actual_row_count), (3, 3));
+        assert!(!diff.is_misordered);
+    }
+
+    #[test]
+    fn reports_missing_and_extra_columns() {
+        let diff = Diff::new(
+            result(&["a", "c"], &[&[1, 2]]),
+            result(&["a", "b"], &[&[1, 2]]),
+            false,
+            &Grading::default(),
+        );
+
+        assert_eq!(diff.missing_columns, vec!["b".to_string()]);
+        assert_eq!(diff.extra_columns, vec!["c".to_string()]);
+        assert!(diff.missing_rows.is_empty() && diff.extra_rows.is_empty());
+    }
+
+    #[test]
+    fn column_policy_hides_renamed_columns() {
+        let grading = Grading {
+            columns: ColumnPolicy::IgnoreAliases,
+            ..Grading::default()
+        };
+        let diff = Diff::new(
+            result(&["x"], &[&[1]]),
+            result(&["a"], &[&[1]]),
+            false,
+            &grading,
+        );
+
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn reports_misordered_rows_only_when_order_matters() {
+        let actual = || result(&["a"], &[&[2], &[1]]);
+        let expected = || result(&["a"], &[&[1], &[2]]);
+
+        let ordered = Diff::new(actual(), expected(), true, &Grading::default());
+        let unordered = Diff::new(actual(), expected(), false, &Grading::default());
+
+        assert!(ordered.is_misordered);
+        assert!(ordered.missing_rows.is_empty() && ordered.extra_rows.is_empty());
+        assert!(!unordered.is_misordered);
+    }
+
+    #[test]
+    fn reports_misordered_columns_under_the_strict_policy() {
+        let actual = || result(&["b", "a"], &[&[2, 1]]);
+        let expected = || result(&["a", "b"], &[&[1, 2]]);
+
+        let strict = Diff::new(actual(), expected(), false, &Grading::default());
+        let ignore_order = Diff::new(
+            actual(),
+            expected(),
+            false,
+            &Grading {
+                columns: ColumnPolicy::IgnoreOrder,
+                ..Grading::default()
+            },
+        );
+
+        assert!(strict.is_column_misordered);
+        assert!(strict.missing_columns.is_empty() && strict.extra_columns.is_empty());
+        assert!(!strict.is_empty());
+        assert!(ignore_order.is_empty());
+    }
+}
@@ -1 +1,4 @@
+pub mod diff;
 pub mod query;
+pub mod scoring;
+pub mod snapshot;
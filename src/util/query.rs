@@ -1,14 +1,320 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    sync::{
+        Arc,
+        atomic::{self, AtomicBool},
+    },
+    time::{Duration, Instant},
+};
+
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use rusqlite::{Connection, types::Value};
+use rusqlite::{
+    Batch, Connection, ErrorCode, Statement,
+    fallible_iterator::FallibleIterator,
+    hooks::{AuthAction, AuthContext, Authorization},
+    types::Value,
+};
+use serde::Deserialize;
+
+use crate::util::snapshot::Snapshot;
 
 pub type Row = Vec<Value>;
+pub type ResultSet = (Vec<String>, Vec<Row>);
+
+#[derive(Debug)]
+pub enum Output {
+    Rows(ResultSet),
+    Changes(u64),
+}
+
+/// Outcome of one statement of a player script.
+#[derive(Debug)]
+pub struct StatementResult {
+    pub sql: String,
+    pub output: Output,
+}
+
+/// Wall-clock budget for a single player query.
+pub const TIME_LIMIT: Duration = Duration::from_secs(2);
+/// Maximum number of rows collected from a single player query.
+pub const ROW_LIMIT: usize = 10_000;
+/// Number of SQLite virtual machine instructions between deadline checks.
+const PROGRESS_STEP: i32 = 1_000;
+
+/// Shared flag used to abort a query running on another thread.
+#[derive(Debug, Default, Clone)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, atomic::Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(atomic::Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Sqlite(rusqlite::Error),
+    Timeout,
+    Cancelled,
+    TooManyRows,
+    NotAllowed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Sqlite(error) => error.fmt(f),
+            Error::Timeout => write!(
+                f,
+                "Query aborted: it ran longer than {} seconds",
+                TIME_LIMIT.as_secs()
+            ),
+            Error::Cancelled => write!(f, "Query cancelled"),
+            Error::TooManyRows => {
+                write!(f, "Query aborted: it returned more than {ROW_LIMIT} rows")
+            }
+            Error::NotAllowed => write!(f, "This statement is not allowed for this question"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        match error.sqlite_error_code() {
+            Some(ErrorCode::OperationInterrupted) => Error::Timeout,
+            Some(ErrorCode::AuthorizationForStatementDenied) => Error::NotAllowed,
+            _ => Error::Sqlite(error),
+        }
+    }
+}
+
+/// Kinds of statements a question lets the player run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Access {
+    /// Only `SELECT` queries.
+    #[default]
+    Read,
+    /// `INSERT`, `UPDATE` and `DELETE` on top of `Read`.
+    Write,
+    /// Creating, altering and dropping tables, indexes, views and triggers on
+    /// top of `Write`.
+    Schema,
+}
+
+/// Pragmas taking an argument that only inspect the schema.
+const INSPECTION_PRAGMAS: [&str; 7] = [
+    "table_info",
+    "table_xinfo",
+    "table_list",
+    "index_list",
+    "index_info",
+    "index_xinfo",
+    "foreign_key_list",
+];
 
-pub fn run(query: &str, schema: &str) -> rusqlite::Result<(Vec<String>, Vec<Row>)> {
-    let conn = Connection::open_in_memory()?;
+fn authorize(access: Access, context: AuthContext<'_>) -> Authorization {
+    let required = match context.action {
+        AuthAction::Select | AuthAction::Read { .. } | AuthAction::Recursive => Access::Read,
+        AuthAction::Function { function_name } => {
+            if function_name.eq_ignore_ascii_case("load_extension") {
+                return Authorization::Deny;
+            }
+
+            Access::Read
+        }
+        AuthAction::Pragma {
+            pragma_name,
+            pragma_value,
+        } => {
+            let is_inspection = INSPECTION_PRAGMAS
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(pragma_name));
+
+            if pragma_value.is_some() && !is_inspection {
+                return Authorization::Deny;
+            }
+
+            Access::Read
+        }
+        AuthAction::Insert { .. }
+        | AuthAction::Update { .. }
+        | AuthAction::Delete { .. }
+        | AuthAction::Transaction { .. }
+        | AuthAction::Savepoint { .. } => Access::Write,
+        AuthAction::CreateIndex { .. }
+        | AuthAction::CreateTable { .. }
+        | AuthAction::CreateTempIndex { .. }
+        | AuthAction::CreateTempTable { .. }
+        | AuthAction::CreateTempTrigger { .. }
+        | AuthAction::CreateTempView { .. }
+        | AuthAction::CreateTrigger { .. }
+        | AuthAction::CreateView { .. }
+        | AuthAction::DropIndex { .. }
+        | AuthAction::DropTable { .. }
+        | AuthAction::DropTempIndex { .. }
+        | AuthAction::DropTempTable { .. }
+        | AuthAction::DropTempTrigger { .. }
+        | AuthAction::DropTempView { .. }
+        | AuthAction::DropTrigger { .. }
+        | AuthAction::DropView { .. }
+        | AuthAction::AlterTable { .. }
+        | AuthAction::Reindex { .. }
+        | AuthAction::Analyze { .. } => Access::Schema,
+        _ => return Authorization::Deny,
+    };
+
+    if required <= access {
+        Authorization::Allow
+    } else {
+        Authorization::Deny
+    }
+}
+
+/// Restores a fresh copy of `database`, then restricts it to `access` and to
+/// the time budget of a player query.
+fn open(database: &Snapshot, access: Access, cancel: &CancelToken) -> Result<Connection, Error> {
+    let conn = database.restore()?;
+
+    conn.authorizer(Some(move |context: AuthContext<'_>| {
+        authorize(access, context)
+    }));
+
+    let deadline = Instant::now() + TIME_LIMIT;
+    let handler_cancel = cancel.clone();
+    conn.progress_handler(
+        PROGRESS_STEP,
+        Some(move || handler_cancel.is_cancelled() || Instant::now() > deadline),
+    );
+
+    Ok(conn)
+}
+
+fn check_cancelled(error: Error, cancel: &CancelToken) -> Error {
+    match error {
+        Error::Timeout if cancel.is_cancelled() => Error::Cancelled,
+        error => error,
+    }
+}
+
+fn execute_all(conn: &Connection, script: &str) -> Result<Vec<StatementResult>, Error> {
+    let mut batch = Batch::new(conn, script);
+    let mut results = Vec::new();
+
+    while let Some(mut stmt) = batch.next()? {
+        let sql = stmt.expanded_sql().unwrap_or_default().trim().to_string();
+        let output = if stmt.column_count() > 0 {
+            Output::Rows(collect_rows(&mut stmt)?)
+        } else {
+            // `sqlite3_changes` is not reset by DDL, so measure the difference.
+            let before = conn.total_changes();
+            stmt.execute(())?;
+            Output::Changes(conn.total_changes() - before)
+        };
+
+        results.push(StatementResult { sql, output });
+    }
+
+    Ok(results)
+}
+
+/// Executes every statement of `script` in order, keeping each one's result.
+pub fn run_all(
+    script: &str,
+    database: &Snapshot,
+    access: Access,
+    cancel: &CancelToken,
+) -> Result<Vec<StatementResult>, Error> {
+    let conn = open(database, access, cancel)?;
+
+    execute_all(&conn, script).map_err(|error| check_cancelled(error, cancel))
+}
 
-    conn.execute_batch(schema)?;
+/// Executes every statement of `query` and returns the rows of the last one
+/// producing any, which is the one used for grading.
+pub fn run(
+    query: &str,
+    database: &Snapshot,
+    access: Access,
+    cancel: &CancelToken,
+) -> Result<ResultSet, Error> {
+    let results = run_all(query, database, access, cancel)?;
 
-    let mut stmt = conn.prepare(query)?;
+    Ok(results
+        .into_iter()
+        .rev()
+        .find_map(|result| match result.output {
+            Output::Rows(data) => Some(data),
+            Output::Changes(_) => None,
+        })
+        .unwrap_or_default())
+}
+
+/// Queries dumping the whole database, used when a state question does not
+/// declare its own verification queries.
+fn dump_queries(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_schema \
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' \
+         ORDER BY name",
+    )?;
+    let tables = stmt
+        .query_map((), |row| row.get::<_, String>(0))?
+        .map(|name| name.map(|name| format!("SELECT * FROM \"{}\"", name.replace('"', "\"\""))));
+
+    let objects =
+        "SELECT type, tbl_name FROM sqlite_schema WHERE name NOT LIKE 'sqlite_%'".to_string();
+
+    std::iter::once(Ok(objects)).chain(tables).collect()
+}
+
+fn verify_script(
+    conn: &Connection,
+    script: &str,
+    verify: &[String],
+) -> Result<Vec<(String, ResultSet)>, Error> {
+    conn.execute_batch(script)?;
+
+    // Verification queries are trusted and may read anything.
+    conn.authorizer(None::<fn(AuthContext<'_>) -> Authorization>);
+
+    let queries = match verify {
+        [] => dump_queries(conn)?,
+        verify => verify.to_vec(),
+    };
+
+    queries
+        .into_iter()
+        .map(|query| collect(conn, &query).map(|result| (query, result)))
+        .collect()
+}
+
+/// Executes `script` on top of `database`, then collects the result of every
+/// `verify` query (or a dump of every table) from the modified database.
+pub fn run_script(
+    script: &str,
+    database: &Snapshot,
+    verify: &[String],
+    access: Access,
+    cancel: &CancelToken,
+) -> Result<Vec<(String, ResultSet)>, Error> {
+    let conn = open(database, access, cancel)?;
+
+    verify_script(&conn, script, verify).map_err(|error| check_cancelled(error, cancel))
+}
+
+fn collect(conn: &Connection, query: &str) -> Result<ResultSet, Error> {
+    collect_rows(&mut conn.prepare(query)?)
+}
+
+fn collect_rows(stmt: &mut Statement<'_>) -> Result<ResultSet, Error> {
     let column_count = stmt.column_count();
 
     let header = stmt
@@ -23,18 +329,719 @@ pub fn run(query: &str, schema: &str) -> rusqlite::Result<(Vec<String>, Vec<Row>
                 .map(|idx| row.get_ref(idx).map(Value::from))
                 .collect::<Result<Row, _>>()
         })?
-        .collect::<Result<_, _>>()?;
+        .take(ROW_LIMIT + 1)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if rows.len() > ROW_LIMIT {
+        return Err(Error::TooManyRows);
+    }
 
     Ok((header, rows))
 }
 
-pub fn is_equal(first: &str, second: &str, schema: &str) -> rusqlite::Result<bool> {
-    let [first, second] = [first, second]
+/// Upper-cased keywords of `query` that are not nested inside parentheses,
+/// skipping string literals, quoted identifiers and comments.
+fn top_level_keywords(query: &str) -> Vec<String> {
+    let mut keywords = Vec::new();
+    let mut chars = query.chars().peekable();
+    let mut depth = 0usize;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' | '"' | '`' => {
+                for next in chars.by_ref() {
+                    if next == ch {
+                        break;
+                    }
+                }
+            }
+            '[' => for _ in chars.by_ref().take_while(|&next| next != ']') {},
+            '-' if chars.peek() == Some(&'-') => {
+                for _ in chars.by_ref().take_while(|&next| next != '\n') {}
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ch if ch.is_alphabetic() || ch == '_' => {
+                let mut word = ch.to_uppercase().to_string();
+                while let Some(&next) = chars.peek() {
+                    if !next.is_alphanumeric() && next != '_' {
+                        break;
+                    }
+                    word.extend(next.to_uppercase());
+                    chars.next();
+                }
+
+                if depth == 0 {
+                    keywords.push(word);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    keywords
+}
+
+/// Whether the outermost statement of `query` sorts its result.
+pub fn is_ordered(query: &str) -> bool {
+    top_level_keywords(query)
+        .windows(2)
+        .any(|pair| pair[0] == "ORDER" && pair[1] == "BY")
+}
+
+/// Whether the outermost statement of `query` caps how many rows it returns.
+pub fn is_limited(query: &str) -> bool {
+    top_level_keywords(query)
+        .iter()
+        .any(|keyword| keyword == "LIMIT")
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Integer(_) | Value::Real(_) => 1,
+        Value::Text(_) => 2,
+        Value::Blob(_) => 3,
+    }
+}
+
+/// Total order on values following SQLite's own sort order, so rows can be
+/// compared as a multiset.
+fn compare_value(first: &Value, second: &Value) -> Ordering {
+    match (first, second) {
+        (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+        (Value::Integer(a), Value::Real(b)) => (*a as f64).total_cmp(b),
+        (Value::Real(a), Value::Integer(b)) => a.total_cmp(&(*b as f64)),
+        (Value::Real(a), Value::Real(b)) => a.total_cmp(b),
+        (Value::Text(a), Value::Text(b)) => a.cmp(b),
+        (Value::Blob(a), Value::Blob(b)) => a.cmp(b),
+        _ => type_rank(first).cmp(&type_rank(second)),
+    }
+}
+
+pub(crate) fn compare_row(first: &Row, second: &Row) -> Ordering {
+    first
+        .iter()
+        .zip(second)
+        .map(|(a, b)| compare_value(a, b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| first.len().cmp(&second.len()))
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnPolicy {
+    /// Column names and their order must match the answer.
+    #[default]
+    Strict,
+    /// Column names are ignored, only their position matters.
+    IgnoreAliases,
+    /// Columns are matched by name, in any order.
+    IgnoreOrder,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Grading {
+    pub columns: ColumnPolicy,
+    /// Number of decimal places `REAL` values are rounded to before comparing.
+    pub precision: Option<u32>,
+    /// Treat `INTEGER` and `REAL` values as equal when they hold the same number.
+    pub coerce_numbers: bool,
+}
+
+/// Relative tolerance for `REAL` values, absorbing rounding differences
+/// between equivalent expressions.
+const EPSILON: f64 = 1e-9;
+
+fn normalize_value(value: Value, grading: &Grading) -> Value {
+    let value = match value {
+        Value::Integer(n) if grading.coerce_numbers => Value::Real(n as f64),
+        value => value,
+    };
+
+    match (value, grading.precision) {
+        (Value::Real(n), Some(precision)) => {
+            let scale = 10f64.powi(precision as i32);
+            Value::Real((n * scale).round() / scale)
+        }
+        (value, _) => value,
+    }
+}
+
+fn is_value_equal(first: &Value, second: &Value) -> bool {
+    match (first, second) {
+        (Value::Real(a), Value::Real(b)) => {
+            a == b || (a - b).abs() <= EPSILON * a.abs().max(b.abs()).max(1.0)
+        }
+        _ => first == second,
+    }
+}
+
+pub(crate) fn is_row_equal(first: &Row, second: &Row) -> bool {
+    first.len() == second.len() && first.iter().zip(second).all(|(a, b)| is_value_equal(a, b))
+}
+
+/// Rows of `first` without an equal counterpart in `second`, both taken as
+/// multisets. Exact sorting can put nearly equal `REAL` values on either side
+/// of another row, so counterparts are searched for instead of paired by
+/// position. Sorting both sides first keeps the search short.
+pub(crate) fn subtract_rows(first: &[Row], second: &[Row]) -> Vec<Row> {
+    let mut used = vec![false; second.len()];
+    let mut first_unused = 0;
+
+    first
+        .iter()
+        .filter(|row| {
+            while first_unused < used.len() && used[first_unused] {
+                first_unused += 1;
+            }
+
+            let counterpart = (first_unused..second.len())
+                .find(|&index| !used[index] && is_row_equal(row, &second[index]));
+
+            match counterpart {
+                Some(index) => {
+                    used[index] = true;
+                    false
+                }
+                None => true,
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// Position of each `expected` column inside `actual`, or `None` when the
+/// headers cannot be matched under `policy`.
+fn match_columns(
+    actual: &[String],
+    expected: &[String],
+    policy: ColumnPolicy,
+) -> Option<Vec<usize>> {
+    if actual.len() != expected.len() {
+        return None;
+    }
+
+    match policy {
+        ColumnPolicy::Strict => (actual == expected).then(|| (0..expected.len()).collect()),
+        ColumnPolicy::IgnoreAliases => Some((0..expected.len()).collect()),
+        ColumnPolicy::IgnoreOrder => {
+            let mut used = vec![false; actual.len()];
+
+            expected
+                .iter()
+                .map(|name| {
+                    let index = (0..actual.len()).find(|&i| !used[i] && &actual[i] == name)?;
+                    used[index] = true;
+                    Some(index)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Normalizes the rows of both results and reorders the columns of `first`
+/// to match `second`, or returns `None` when their columns differ.
+pub(crate) fn align_rows(
+    (first_header, first_rows): ResultSet,
+    (second_header, second_rows): ResultSet,
+    grading: &Grading,
+) -> Option<(Vec<Row>, Vec<Row>)> {
+    let columns = match_columns(&first_header, &second_header, grading.columns)?;
+
+    let first_rows = first_rows
+        .into_iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|&index| normalize_value(row[index].clone(), grading))
+                .collect()
+        })
+        .collect();
+    let second_rows = second_rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|value| normalize_value(value, grading))
+                .collect()
+        })
+        .collect();
+
+    Some((first_rows, second_rows))
+}
+
+fn is_result_equal(
+    first: ResultSet,
+    second: ResultSet,
+    is_ordered: bool,
+    grading: &Grading,
+) -> bool {
+    let Some((mut first_rows, mut second_rows)) = align_rows(first, second, grading) else {
+        return false;
+    };
+
+    if first_rows.len() != second_rows.len() {
+        return false;
+    }
+
+    if is_ordered {
+        return first_rows
+            .iter()
+            .zip(&second_rows)
+            .all(|(a, b)| is_row_equal(a, b));
+    }
+
+    first_rows.sort_by(compare_row);
+    second_rows.sort_by(compare_row);
+
+    subtract_rows(&first_rows, &second_rows).is_empty()
+}
+
+/// Checks whether `query` produces the same result as `answer`. Rows are
+/// compared in order only when `answer` itself has a top-level `ORDER BY`.
+pub fn is_equal(
+    query: &str,
+    answer: &str,
+    database: &Snapshot,
+    grading: &Grading,
+    access: Access,
+    cancel: &CancelToken,
+) -> Result<bool, Error> {
+    let [first, second] = [query, answer]
+        .par_iter()
+        .map(|&query| run(query, database, access, cancel))
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .unwrap();
+
+    Ok(is_result_equal(first, second, is_ordered(answer), grading))
+}
+
+/// Checks whether `script` leaves the database in the same state as `answer`,
+/// as observed through the `verify` queries.
+pub fn is_state_equal(
+    script: &str,
+    answer: &str,
+    database: &Snapshot,
+    verify: &[String],
+    grading: &Grading,
+    access: Access,
+    cancel: &CancelToken,
+) -> Result<bool, Error> {
+    let [first, second] = [script, answer]
         .par_iter()
-        .map(|&query| run(query, schema))
+        .map(|&script| run_script(script, database, verify, access, cancel))
         .collect::<Result<Vec<_>, _>>()?
         .try_into()
         .unwrap();
 
-    Ok(first == second)
+    Ok(first.len() == second.len()
+        && first
+            .into_iter()
+            .zip(second)
+            .all(|((first_query, first), (second_query, second))| {
+                first_query == second_query
+                    && is_result_equal(first, second, is_ordered(&second_query), grading)
+            }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENDLESS: &str =
+        "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) SELECT count(*) FROM n";
+
+    fn header(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn int_rows(rows: &[&[i64]]) -> Vec<Row> {
+        rows.iter()
+            .map(|row| row.iter().map(|&n| Value::Integer(n)).collect())
+            .collect()
+    }
+
+    fn snapshot(script: &str) -> Snapshot {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(script).unwrap();
+
+        Snapshot::from_connection(&conn).unwrap()
+    }
+
+    #[test]
+    fn is_ordered_detects_top_level_order_by() {
+        assert!(is_ordered("SELECT * FROM t ORDER BY a"));
+        assert!(is_ordered("select * from t order\n  by a desc"));
+        assert!(!is_ordered("SELECT * FROM t"));
+    }
+
+    #[test]
+    fn is_ordered_ignores_nested_order_by() {
+        assert!(!is_ordered(
+            "SELECT * FROM (SELECT * FROM t ORDER BY a LIMIT 3)"
+        ));
+        assert!(!is_ordered(
+            "SELECT a, ROW_NUMBER() OVER (ORDER BY a) FROM t"
+        ));
+        assert!(!is_ordered(
+            "SELECT * FROM t WHERE a IN (SELECT a FROM u ORDER BY a)"
+        ));
+        assert!(is_ordered(
+            "SELECT a, RANK() OVER (ORDER BY b) FROM t ORDER BY a"
+        ));
+    }
+
+    #[test]
+    fn is_ordered_ignores_strings_and_comments() {
+        assert!(!is_ordered("SELECT 'ORDER BY a' FROM t"));
+        assert!(!is_ordered("SELECT \"order by\" FROM t"));
+        assert!(!is_ordered("SELECT [order by] FROM t"));
+        assert!(!is_ordered("SELECT a FROM t -- ORDER BY a"));
+        assert!(!is_ordered("SELECT a FROM t /* ORDER BY a */"));
+        assert!(is_ordered("SELECT a FROM t /* ) */ ORDER BY a"));
+    }
+
+    #[test]
+    fn is_limited_ignores_nested_limit() {
+        assert!(is_limited("SELECT * FROM t LIMIT 3"));
+        assert!(!is_limited("SELECT * FROM (SELECT * FROM t LIMIT 3)"));
+        assert!(!is_limited("SELECT 'LIMIT 3' FROM t"));
+    }
+
+    #[test]
+    fn compare_row_follows_sqlite_order() {
+        let null = vec![Value::Null];
+        let integer = vec![Value::Integer(2)];
+        let real = vec![Value::Real(1.5)];
+        let text = vec![Value::Text("a".to_string())];
+        let blob = vec![Value::Blob(vec![0])];
+        let longer = vec![Value::Integer(2), Value::Null];
+
+        assert_eq!(compare_row(&null, &integer), Ordering::Less);
+        assert_eq!(compare_row(&real, &integer), Ordering::Less);
+        assert_eq!(compare_row(&integer, &text), Ordering::Less);
+        assert_eq!(compare_row(&text, &blob), Ordering::Less);
+        assert_eq!(compare_row(&integer, &integer), Ordering::Equal);
+        assert_eq!(compare_row(&integer, &longer), Ordering::Less);
+    }
+
+    #[test]
+    fn match_columns_strict() {
+        let expected = header(&["a", "b"]);
+
+        assert_eq!(
+            match_columns(&header(&["a", "b"]), &expected, ColumnPolicy::Strict),
+            Some(vec![0, 1])
+        );
+        assert_eq!(
+            match_columns(&header(&["b", "a"]), &expected, ColumnPolicy::Strict),
+            None
+        );
+        assert_eq!(
+            match_columns(&header(&["a", "c"]), &expected, ColumnPolicy::Strict),
+            None
+        );
+    }
+
+    #[test]
+    fn match_columns_ignore_aliases() {
+        let expected = header(&["a", "b"]);
+
+        assert_eq!(
+            match_columns(&header(&["x", "y"]), &expected, ColumnPolicy::IgnoreAliases),
+            Some(vec![0, 1])
+        );
+        assert_eq!(
+            match_columns(&header(&["x"]), &expected, ColumnPolicy::IgnoreAliases),
+            None
+        );
+    }
+
+    #[test]
+    fn match_columns_ignore_order() {
+        let expected = header(&["a", "b", "a"]);
+
+        assert_eq!(
+            match_columns(
+                &header(&["b", "a", "a"]),
+                &expected,
+                ColumnPolicy::IgnoreOrder
+            ),
+            Some(vec![1, 0, 2])
+        );
+        assert_eq!(
+            match_columns(
+                &header(&["b", "a", "c"]),
+                &expected,
+                ColumnPolicy::IgnoreOrder
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn unordered_results_compare_as_multisets() {
+        let grading = Grading::default();
+        let result = |rows| (header(&["a"]), int_rows(rows));
+
+        assert!(is_result_equal(
+            result(&[&[1], &[2], &[2]]),
+            result(&[&[2], &[1], &[2]]),
+            false,
+            &grading
+        ));
+        assert!(!is_result_equal(
+            result(&[&[1], &[1], &[2]]),
+            result(&[&[1], &[2], &[2]]),
+            false,
+            &grading
+        ));
+        assert!(!is_result_equal(
+            result(&[&[1], &[2]]),
+            result(&[&[1], &[2], &[2]]),
+            false,
+            &grading
+        ));
+    }
+
+    #[test]
+    fn ordered_results_compare_row_by_row() {
+        let grading = Grading::default();
+        let result = |rows| (header(&["a"]), int_rows(rows));
+
+        assert!(is_result_equal(
+            result(&[&[1], &[2]]),
+            result(&[&[1], &[2]]),
+            true,
+            &grading
+        ));
+        assert!(!is_result_equal(
+            result(&[&[2], &[1]]),
+            result(&[&[1], &[2]]),
+            true,
+            &grading
+        ));
+    }
+
+    #[test]
+    fn nearly_equal_reals_match_whatever_their_sort_order() {
+        let grading = Grading::default();
+        let almost_one = 1.0 - f64::EPSILON;
+        let first = vec![
+            vec![Value::Real(1.0), Value::Integer(1)],
+            vec![Value::Real(almost_one), Value::Integer(2)],
+        ];
+        let second = vec![
+            vec![Value::Real(almost_one), Value::Integer(1)],
+            vec![Value::Real(1.0), Value::Integer(2)],
+        ];
+
+        assert!(is_result_equal(
+            (header(&["x", "n"]), first),
+            (header(&["x", "n"]), second),
+            false,
+            &grading
+        ));
+    }
+
+    #[test]
+    fn grading_rounds_and_coerces_numbers() {
+        let result = |value| (header(&["a"]), vec![vec![value]]);
+        let lenient = Grading {
+            precision: Some(2),
+            coerce_numbers: true,
+            ..Grading::default()
+        };
+
+        assert!(is_result_equal(
+            result(Value::Real(0.3333)),
+            result(Value::Real(0.33)),
+            false,
+            &lenient
+        ));
+        assert!(is_result_equal(
+            result(Value::Integer(2)),
+            result(Value::Real(2.0)),
+            false,
+            &lenient
+        ));
+        assert!(!is_result_equal(
+            result(Value::Integer(2)),
+            result(Value::Real(2.0)),
+            false,
+            &Grading::default()
+        ));
+    }
+
+    #[test]
+    fn column_policy_applies_before_comparing_rows() {
+        let ignore_order = Grading {
+            columns: ColumnPolicy::IgnoreOrder,
+            ..Grading::default()
+        };
+
+        assert!(is_result_equal(
+            (header(&["b", "a"]), int_rows(&[&[2, 1]])),
+            (header(&["a", "b"]), int_rows(&[&[1, 2]])),
+            false,
+            &ignore_order
+        ));
+        assert!(!is_result_equal(
+            (header(&["b", "a"]), int_rows(&[&[2, 1]])),
+            (header(&["a", "b"]), int_rows(&[&[1, 2]])),
+            false,
+            &Grading::default()
+        ));
+    }
+
+    #[test]
+    fn runaway_queries_time_out() {
+        let result = run(
+            ENDLESS,
+            &snapshot(""),
+            Access::Read,
+            &CancelToken::default(),
+        );
+
+        assert!(matches!(result, Err(Error::Timeout)));
+    }
+
+    #[test]
+    fn cancelled_queries_report_the_cancellation() {
+        let cancel = CancelToken::default();
+        cancel.cancel();
+
+        let result = run(ENDLESS, &snapshot(""), Access::Read, &cancel);
+
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn results_are_limited_to_row_limit_rows() {
+        let numbers = |count: usize| {
+            format!(
+                "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n LIMIT {count}) \
+                 SELECT x FROM n"
+            )
+        };
+        let database = snapshot("");
+        let cancel = CancelToken::default();
+
+        let (_, rows) = run(&numbers(ROW_LIMIT), &database, Access::Read, &cancel).unwrap();
+        assert_eq!(rows.len(), ROW_LIMIT);
+        assert!(matches!(
+            run(&numbers(ROW_LIMIT + 1), &database, Access::Read, &cancel),
+            Err(Error::TooManyRows)
+        ));
+    }
+
+    #[test]
+    fn escaping_the_sandbox_is_denied() {
+        let database = snapshot("CREATE TABLE t (x INT)");
+        let cancel = CancelToken::default();
+
+        for query in [
+            "ATTACH ':memory:' AS other",
+            "SELECT load_extension('evil')",
+            "PRAGMA writable_schema = 1",
+        ] {
+            let is_denied = match run(query, &database, Access::Schema, &cancel) {
+                Err(Error::NotAllowed) => true,
+                // SQLite reports a denied function as a plain error.
+                Err(Error::Sqlite(error)) => error.to_string().contains("not authorized"),
+                _ => false,
+            };
+            assert!(is_denied, "{query}");
+        }
+        assert!(run("PRAGMA table_info(t)", &database, Access::Read, &cancel).is_ok());
+    }
+
+    #[test]
+    fn statements_need_the_matching_access() {
+        let database = snapshot("CREATE TABLE t (x INT)");
+        let cancel = CancelToken::default();
+        let is_allowed = |query, access| match run(query, &database, access, &cancel) {
+            Ok(_) => true,
+            Err(Error::NotAllowed) => false,
+            Err(error) => panic!("{query}: {error}"),
+        };
+
+        let insert = "INSERT INTO t VALUES (1)";
+        assert!(!is_allowed(insert, Access::Read));
+        assert!(is_allowed(insert, Access::Write));
+
+        let create = "CREATE TABLE u (y INT)";
+        assert!(!is_allowed(create, Access::Read));
+        assert!(!is_allowed(create, Access::Write));
+        assert!(is_allowed(create, Access::Schema));
+
+        let drop = "DROP TABLE t";
+        assert!(!is_allowed(drop, Access::Write));
+        assert!(is_allowed(drop, Access::Schema));
+    }
+
+    #[test]
+    fn scripts_report_every_statement_and_grade_the_last_select() {
+        let database = snapshot("CREATE TABLE t (x INT); INSERT INTO t VALUES (1), (2);");
+        let cancel = CancelToken::default();
+        let script = "INSERT INTO t VALUES (3);
+            SELECT count(*) FROM t;
+            UPDATE t SET x = x * 10;
+            SELECT x FROM t;";
+
+        let results = run_all(script, &database, Access::Write, &cancel).unwrap();
+        let outputs = results
+            .iter()
+            .map(|result| match &result.output {
+                Output::Rows((_, rows)) => format!("{rows:?}"),
+                Output::Changes(changes) => format!("{changes} changed"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            [
+                "1 changed".to_string(),
+                format!("{:?}", int_rows(&[&[3]])),
+                "3 changed".to_string(),
+                format!("{:?}", int_rows(&[&[10], &[20], &[30]])),
+            ]
+        );
+        assert_eq!(results[0].sql, "INSERT INTO t VALUES (3);");
+
+        let grading = Grading::default();
+        assert!(
+            is_equal(
+                "SELECT 1; SELECT x FROM t",
+                "SELECT x FROM t",
+                &database,
+                &grading,
+                Access::Read,
+                &cancel
+            )
+            .unwrap()
+        );
+        assert!(
+            !is_equal(
+                "SELECT x FROM t; SELECT 1",
+                "SELECT x FROM t",
+                &database,
+                &grading,
+                Access::Read,
+                &cancel
+            )
+            .unwrap()
+        );
+    }
 }
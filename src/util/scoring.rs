@@ -0,0 +1,271 @@
+use serde::Deserialize;
+
+use crate::util::diff::Diff;
+
+/// Points awarded for a fully correct easy question.
+pub const QUESTION_POINTS: u64 = 100;
+
+/// Share of an incorrect answer's credit that comes from its columns, its
+/// rows and its row count respectively.
+const COLUMN_WEIGHT: f64 = 0.3;
+const ROW_WEIGHT: f64 = 0.5;
+const ROW_COUNT_WEIGHT: f64 = 0.2;
+
+/// Highest credit an incorrect answer can earn, so a correct answer always
+/// scores strictly more.
+const MAX_PARTIAL_CREDIT: f64 = 0.8;
+
+/// Rows in the right order count for more than rows in the wrong one.
+const MISORDERED_PENALTY: f64 = 0.5;
+
+/// Share of a question's points added on top when it is solved the moment
+/// the game starts, shrinking linearly as the timer runs down.
+const TIME_BONUS: f64 = 0.5;
+
+/// Share of a question's points lost for each wrong submission, and the most
+/// that can be lost that way. A solved question never drops below
+/// `MAX_PARTIAL_CREDIT`.
+const WRONG_SUBMISSION_PENALTY: f64 = 0.1;
+const MAX_PENALTY: f64 = 0.5;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    #[default]
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// Points a question of this difficulty is worth unless it sets its own.
+    pub fn points(self) -> u64 {
+        match self {
+            Difficulty::Easy => QUESTION_POINTS,
+            Difficulty::Medium => QUESTION_POINTS * 2,
+            Difficulty::Hard => QUESTION_POINTS * 3,
+        }
+    }
+}
+
+/// Everything the player did on one question that affects its score.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Attempt {
+    /// Fraction of the question's points earned, `1.0` once solved.
+    pub credit: f64,
+    pub wrong_submissions: u32,
+    /// Seconds left on the timer when the question was solved.
+    pub seconds_left: Option<u64>,
+}
+
+impl Attempt {
+    pub fn is_solved(&self) -> bool {
+        self.seconds_left.is_some()
+    }
+
+    /// Marks the question as solved with `seconds_left` on the timer. Solving
+    /// it again does not change its score.
+    pub fn solve(&mut self, seconds_left: u64) {
+        if self.is_solved() {
+            return;
+        }
+
+        self.credit = 1.0;
+        self.seconds_left = Some(seconds_left);
+    }
+
+    /// Records a wrong submission, keeping the best credit earned so far.
+    pub fn fail(&mut self, credit: f64) {
+        if self.is_solved() {
+            return;
+        }
+
+        self.wrong_submissions += 1;
+        self.credit = self.credit.max(credit);
+    }
+
+    /// Points earned for a question worth `points`, in a game lasting
+    /// `game_duration` seconds.
+    pub fn score(&self, points: u64, game_duration: u64) -> u64 {
+        let points = points as f64;
+
+        let time_bonus = match self.seconds_left {
+            Some(seconds_left) if game_duration > 0 => {
+                points * TIME_BONUS * seconds_left.min(game_duration) as f64 / game_duration as f64
+            }
+            _ => 0.0,
+        };
+        let penalty =
+            points * (WRONG_SUBMISSION_PENALTY * self.wrong_submissions as f64).min(MAX_PENALTY);
+
+        // However many tries it took, solving must beat giving up, which earns
+        // at most `MAX_PARTIAL_CREDIT` minus one wrong submission.
+        let floor = if self.is_solved() {
+            points * MAX_PARTIAL_CREDIT
+        } else {
+            0.0
+        };
+
+        (points * self.credit + time_bonus - penalty)
+            .max(floor)
+            .round() as u64
+    }
+}
+
+fn ratio(matched: usize, total: usize) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        matched as f64 / total as f64
+    }
+}
+
+/// Fraction of the question's points earned by an incorrect answer, judged by
+/// how much of its result overlaps the expected one.
+pub fn partial_credit(diff: &Diff) -> f64 {
+    let expected_columns = diff.header.len();
+    let matched_columns = expected_columns - diff.missing_columns.len();
+    let columns = ratio(
+        matched_columns,
+        expected_columns.max(matched_columns + diff.extra_columns.len()),
+    );
+
+    // Rows can only be compared once the columns line up.
+    let rows = if diff.missing_columns.is_empty()
+        && diff.extra_columns.is_empty()
+        && !diff.is_column_misordered
+    {
+        let matched_rows = diff.expected_row_count - diff.missing_rows.len();
+        let rows = ratio(
+            matched_rows,
+            diff.expected_row_count.max(diff.actual_row_count),
+        );
+
+        if diff.is_misordered {
+            rows * MISORDERED_PENALTY
+        } else {
+            rows
+        }
+    } else {
+        0.0
+    };
+
+    let row_count = if diff.expected_row_count == diff.actual_row_count {
+        1.0
+    } else {
+        0.0
+    };
+
+    (COLUMN_WEIGHT * columns + ROW_WEIGHT * rows + ROW_COUNT_WEIGHT * row_count)
+        * MAX_PARTIAL_CREDIT
+}
+
+/// Credit of an incorrect answer across every dataset it was graded on. The
+/// worst dataset counts, so an answer hard-coded to the shown dataset earns
+/// no more than it does on the hidden ones.
+pub fn lowest_credit(diffs: &[Diff]) -> f64 {
+    // The answer failed without differing on any dataset, so there is no
+    // overlap to reward.
+    if diffs.iter().all(Diff::is_empty) {
+        return 0.0;
+    }
+
+    diffs.iter().map(partial_credit).fold(1.0, f64::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solved_score_includes_time_bonus() {
+        let mut attempt = Attempt::default();
+        attempt.solve(60);
+
+        assert_eq!(attempt.score(100, 120), 125);
+        assert_eq!(attempt.score(100, 0), 100);
+    }
+
+    #[test]
+    fn wrong_submissions_are_penalized_up_to_a_limit() {
+        let mut attempt = Attempt::default();
+        attempt.fail(0.8);
+        assert_eq!(attempt.score(100, 120), 70);
+
+        for _ in 0..10 {
+            attempt.fail(0.0);
+        }
+        assert_eq!(attempt.credit, 0.8);
+        assert_eq!(attempt.score(100, 120), 30);
+    }
+
+    #[test]
+    fn solving_always_beats_giving_up() {
+        let mut given_up = Attempt::default();
+        given_up.fail(MAX_PARTIAL_CREDIT);
+
+        let mut solved_late = Attempt::default();
+        for _ in 0..10 {
+            solved_late.fail(0.0);
+        }
+        solved_late.solve(0);
+
+        assert_eq!(solved_late.score(100, 120), 80);
+        assert!(solved_late.score(100, 120) > given_up.score(100, 120));
+    }
+
+    #[test]
+    fn solving_again_keeps_the_first_score() {
+        let mut attempt = Attempt::default();
+        attempt.solve(120);
+        attempt.solve(0);
+        attempt.fail(0.0);
+
+        assert_eq!(attempt.wrong_submissions, 0);
+        assert_eq!(attempt.score(100, 120), 150);
+    }
+
+    #[test]
+    fn partial_credit_is_capped() {
+        assert_eq!(partial_credit(&Diff::default()), MAX_PARTIAL_CREDIT);
+
+        let no_rows = Diff {
+            header: vec!["a".to_string()],
+            expected_row_count: 2,
+            missing_rows: vec![Vec::new(), Vec::new()],
+            ..Diff::default()
+        };
+        assert_eq!(partial_credit(&no_rows), COLUMN_WEIGHT * MAX_PARTIAL_CREDIT);
+    }
+
+    #[test]
+    fn misordered_columns_earn_no_row_credit() {
+        let diff = Diff {
+            header: vec!["a".to_string(), "b".to_string()],
+            expected_row_count: 2,
+            actual_row_count: 3,
+            is_column_misordered: true,
+            ..Diff::default()
+        };
+
+        assert_eq!(partial_credit(&diff), COLUMN_WEIGHT * MAX_PARTIAL_CREDIT);
+    }
+
+    #[test]
+    fn lowest_credit_counts_the_worst_dataset() {
+        let shown = Diff::default();
+        let hidden = Diff {
+            header: vec!["a".to_string()],
+            expected_row_count: 1,
+            missing_rows: vec![Vec::new()],
+            ..Diff::default()
+        };
+
+        assert_eq!(lowest_credit(&[]), 0.0);
+        assert_eq!(lowest_credit(&[Diff::default(), Diff::default()]), 0.0);
+        assert_eq!(
+            lowest_credit(&[shown, hidden]),
+            COLUMN_WEIGHT * MAX_PARTIAL_CREDIT
+        );
+    }
+}
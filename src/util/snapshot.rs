@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use rusqlite::{Connection, MAIN_DB};
+
+/// Serialized copy of a seeded database, restored into a fresh in-memory
+/// connection for every query instead of replaying its SQL.
+#[derive(Debug, Clone)]
+pub struct Snapshot(Arc<[u8]>);
+
+impl Snapshot {
+    pub fn from_connection(conn: &Connection) -> rusqlite::Result<Self> {
+        let data = conn.serialize(MAIN_DB)?;
+
+        Ok(Self(Arc::from(&*data)))
+    }
+
+    pub fn restore(&self) -> rusqlite::Result<Connection> {
+        let mut conn = Connection::open_in_memory()?;
+
+        // SQLite cannot deserialize an empty image, which is what a database
+        // without any table serializes to.
+        if !self.0.is_empty() {
+            conn.deserialize_read_exact(MAIN_DB, &*self.0, self.0.len(), false)?;
+        }
+
+        Ok(conn)
+    }
+}